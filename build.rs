@@ -0,0 +1,47 @@
+//! Records the commit the crate is built from, so run manifests name the code that produced the
+//! answers instead of whatever is checked out when the runner happens to be started.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Paths whose local changes mark the build as dirty.
+const SOURCES: [&str; 4] = ["src", "data", "Cargo.toml", "build.rs"];
+
+fn main() {
+    for source in SOURCES {
+        println!("cargo:rerun-if-changed={source}");
+    }
+    let git_dir = Path::new(".git");
+    for file in ["HEAD", "index", "packed-refs"] {
+        println!("cargo:rerun-if-changed={}", git_dir.join(file).display());
+    }
+    if let Some(reference) = std::fs::read_to_string(git_dir.join("HEAD"))
+        .ok()
+        .and_then(|head| {
+            head.strip_prefix("ref: ")
+                .map(|name| name.trim().to_owned())
+        })
+    {
+        println!(
+            "cargo:rerun-if-changed={}",
+            git_dir.join(reference).display()
+        );
+    }
+
+    let Some(commit) = git(&["rev-parse", "HEAD"]) else {
+        return;
+    };
+    let mut status_args = vec!["status", "--porcelain", "--untracked-files=no", "--"];
+    status_args.extend(SOURCES);
+    let is_dirty = git(&status_args).is_some_and(|status| !status.is_empty());
+    let suffix = if is_dirty { "-dirty" } else { "" };
+    println!("cargo:rustc-env=AOC_GIT_COMMIT={commit}{suffix}");
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
@@ -0,0 +1,441 @@
+//! Runner for everything around the daily puzzle binaries.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+
+use clap::{Parser, Subcommand};
+
+use aoc_2022::journal::{self, Journal, SolveDuration};
+use aoc_2022::leaderboard::{Leaderboard, LeaderboardReport};
+use aoc_2022::manifest::{Comparison, Manifest};
+use aoc_2022::report::{Format, Report};
+use aoc_2022::vault::{self, VaultKey};
+use aoc_2022::{profile, registry, runner, scaffold};
+
+#[derive(Debug, Parser)]
+struct Args {
+    /// Tracing filter directives per module, e.g. `aoc_2022=debug`.
+    #[arg(long, global = true)]
+    log: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Solve time journal.
+    Journal {
+        /// Journal data file to use instead of `data/journal.txt`.
+        #[arg(long)]
+        file: Option<PathBuf>,
+        #[command(subcommand)]
+        command: JournalCommand,
+    },
+    /// Standings, star times and rank history of a private leaderboard JSON export.
+    Leaderboard {
+        /// JSON file downloaded from the "API" link of the private leaderboard.
+        file: PathBuf,
+        /// Id or name of your own member to compare with the solve time journal.
+        #[arg(long)]
+        member: Option<String>,
+        /// Draws the rank history as a bump chart instead of a table.
+        #[arg(long)]
+        chart: bool,
+        /// Journal data file to use instead of `data/journal.txt`.
+        #[arg(long)]
+        journal: Option<PathBuf>,
+    },
+    /// Run manifests written by `run --manifest` and `run-all --manifest`.
+    Manifest {
+        #[command(subcommand)]
+        command: ManifestCommand,
+    },
+    /// Generates module, binary, input slot and registry entry of a new day.
+    NewDay {
+        day: u8,
+        /// Year of the day instead of the latest one in the registry.
+        #[arg(long)]
+        year: Option<u16>,
+        /// Repository to scaffold into instead of this one.
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+    /// Runs every day and writes one document with answers, timings, solve times and renders.
+    Report {
+        /// Only reports the days of these years instead of all of them.
+        #[arg(long)]
+        year: Vec<u16>,
+        /// Input profile to solve instead of the default one.
+        #[arg(long, value_parser = profile::parse_name)]
+        profile: Option<String>,
+        /// `markdown` or `html`.
+        #[arg(long, default_value = "markdown")]
+        format: Format,
+        /// File to write the report to instead of stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Journal data file to use instead of `data/journal.txt`.
+        #[arg(long)]
+        journal: Option<PathBuf>,
+    },
+    /// Runs every solution of a day and compares the answers, e.g. `run 2022 13`.
+    Run {
+        year: u16,
+        day: u8,
+        /// Solves the example from the puzzle description in `samples/<YEAR>/`.
+        #[arg(long, conflicts_with = "profile")]
+        sample: bool,
+        /// Input profile to solve instead of the default one.
+        #[arg(long, value_parser = profile::parse_name)]
+        profile: Option<String>,
+        /// Writes input hashes, code version, answers and timings of the runs to this file.
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Runs every day against every input profile in parallel and prints a summary table.
+    RunAll {
+        /// Only runs the days of these years instead of all of them.
+        #[arg(long)]
+        year: Vec<u16>,
+        /// Only runs these profiles instead of all in `inputs/<YEAR>/`.
+        #[arg(long, value_parser = profile::parse_name)]
+        profile: Vec<String>,
+        /// Writes input hashes, code version, answers and timings of the runs to this file.
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Encrypted storage of the puzzle inputs.
+    Vault {
+        /// Keyfile to use instead of `.vault-key`; `AOC_VAULT_KEY` takes precedence.
+        #[arg(long)]
+        key_file: Option<PathBuf>,
+        /// Only handles the inputs of these years instead of all of them.
+        #[arg(long)]
+        year: Vec<u16>,
+        /// Only handles these profiles instead of all in `inputs/<YEAR>/`.
+        #[arg(long, value_parser = profile::parse_name)]
+        profile: Vec<String>,
+        #[command(subcommand)]
+        command: VaultCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum JournalCommand {
+    /// Prints totals, averages and part 2 versus part 1 ratios.
+    Report,
+    /// Records a solve attempt, e.g. `record 2022 15 1 1h2m3s "off by one"`.
+    Record {
+        year: u16,
+        day: u8,
+        part: u8,
+        duration: SolveDuration,
+        note: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ManifestCommand {
+    /// Lists the changed answers and whether the input or the code changed.
+    Compare { old: PathBuf, new: PathBuf },
+}
+
+#[derive(Debug, Subcommand)]
+enum VaultCommand {
+    /// Generates a new key into the keyfile.
+    Keygen,
+    /// Encrypts every plain input into its `.vault` file and removes the plain input.
+    Encrypt,
+    /// Restores the plain inputs from their `.vault` files.
+    Decrypt,
+    /// Re-encrypts every vault with a newly generated key and stores it in the keyfile.
+    Rotate,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    aoc_2022::logging::init(args.log.as_deref())?;
+
+    match args.command {
+        Command::Journal { file, command } => {
+            let path = file.unwrap_or_else(journal::default_path);
+            match command {
+                JournalCommand::Report => println!("{}", Journal::load(&path)?.report()),
+                JournalCommand::Record {
+                    year,
+                    day,
+                    part,
+                    duration,
+                    note,
+                } => {
+                    let entry = Journal::record(&path, year, day, part, duration, note)?;
+                    println!("recorded: {entry}");
+                }
+            }
+        }
+        Command::Leaderboard {
+            file,
+            member,
+            chart,
+            journal,
+        } => {
+            let leaderboard = Leaderboard::load(&file)?;
+            let own_member = member
+                .map(|member| {
+                    leaderboard.find_member(&member).ok_or_else(|| {
+                        anyhow::anyhow!("Member \"{member}\" is not on the leaderboard.")
+                    })
+                })
+                .transpose()?;
+            let journal_report = match own_member {
+                Some(_) => {
+                    Some(Journal::load(&journal.unwrap_or_else(journal::default_path))?.report())
+                }
+                None => None,
+            };
+            let report = LeaderboardReport {
+                leaderboard: &leaderboard,
+                chart,
+                own_member,
+                journal: journal_report.as_ref(),
+            };
+            print!("{report}");
+        }
+        Command::Manifest { command } => match command {
+            ManifestCommand::Compare { old, new } => {
+                let old = Manifest::load(&old)?;
+                let new = Manifest::load(&new)?;
+                println!(
+                    "{}",
+                    Comparison {
+                        old: &old,
+                        new: &new
+                    }
+                );
+            }
+        },
+        Command::NewDay { day, year, root } => {
+            let root = root.unwrap_or_else(scaffold::default_root);
+            let year = year
+                .or_else(|| registry::years().last().copied())
+                .unwrap_or(registry::FIRST_YEAR);
+            for path in scaffold::new_day(&root, year, day)? {
+                println!("written: {}", path.display());
+            }
+        }
+        Command::Report {
+            year,
+            profile,
+            format,
+            output,
+            journal,
+        } => {
+            let journal = Journal::load(&journal.unwrap_or_else(journal::default_path))?;
+            let profiles = [profile.unwrap_or_else(|| profile::DEFAULT_PROFILE.to_owned())];
+            let binary_dir = runner::binary_dir()?;
+            let render_root =
+                std::env::temp_dir().join(format!("aoc-renders-{}", std::process::id()));
+            let mut runs = Vec::new();
+            for year in selected_years(year) {
+                let enabled_solutions = registry::solutions(year)
+                    .filter(|solution| solution.enabled)
+                    .copied()
+                    .collect::<Vec<_>>();
+                runs.extend(runner::run_all(
+                    &binary_dir,
+                    &enabled_solutions,
+                    &profiles,
+                    Some(&render_root),
+                ));
+            }
+            if render_root.exists() {
+                std::fs::remove_dir_all(&render_root)
+                    .with_context(|| format!("while removing {}", render_root.display()))?;
+            }
+            for run in runs.iter().filter(|run| run.outcome.is_failure()) {
+                println!("{run}\n");
+            }
+            let report = Report {
+                runs: &runs,
+                journal: &journal,
+                format,
+            };
+            match output {
+                Some(output) => {
+                    std::fs::write(&output, report.to_string())
+                        .with_context(|| format!("while writing report {}", output.display()))?;
+                    println!("written: {}", output.display());
+                }
+                None => print!("{report}"),
+            }
+        }
+        Command::Run {
+            year,
+            day,
+            sample,
+            profile,
+            manifest,
+        } => {
+            let profile = if sample {
+                profile::SAMPLE_PROFILE.to_owned()
+            } else {
+                profile.unwrap_or_else(|| profile::DEFAULT_PROFILE.to_owned())
+            };
+            let solutions = registry::solutions(year)
+                .filter(|solution| solution.day == day)
+                .copied()
+                .collect::<Vec<_>>();
+            if solutions.is_empty() {
+                return Err(anyhow::anyhow!("Day {day} of {year} is not solved."));
+            }
+            let start = Instant::now();
+            let runs = runner::run_all(&runner::binary_dir()?, &solutions, &[profile], None);
+            report_runs(&runs, start.elapsed(), manifest.as_deref())?;
+        }
+        Command::RunAll {
+            year,
+            profile,
+            manifest,
+        } => {
+            let binary_dir = runner::binary_dir()?;
+            let start = Instant::now();
+            let mut runs = Vec::new();
+            for year in selected_years(year) {
+                let profiles = selected_profiles(year, profile.clone())?;
+                let compiled_out_days = registry::compiled_out_days(year);
+                if !compiled_out_days.is_empty() {
+                    println!("compiled out days of {year}: {compiled_out_days:?}");
+                }
+                let enabled_solutions = registry::solutions(year)
+                    .filter(|solution| solution.enabled)
+                    .copied()
+                    .collect::<Vec<_>>();
+                runs.extend(runner::run_all(
+                    &binary_dir,
+                    &enabled_solutions,
+                    &profiles,
+                    None,
+                ));
+            }
+            report_runs(&runs, start.elapsed(), manifest.as_deref())?;
+        }
+        Command::Vault {
+            key_file,
+            year,
+            profile,
+            command,
+        } => {
+            let key_path = key_file.unwrap_or_else(vault::default_key_path);
+            let mut input_paths = Vec::new();
+            for year in selected_years(year) {
+                // The samples are public, so there is nothing to hide.
+                for profile in selected_profiles(year, profile.clone())?
+                    .iter()
+                    .filter(|profile| *profile != profile::SAMPLE_PROFILE)
+                {
+                    input_paths.extend(profile::input_paths(year, profile)?);
+                }
+            }
+            run_vault_command(&command, &key_path, input_paths)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the details of the failed runs and the summary table, writes the manifest if requested
+/// and fails if any run failed.
+fn report_runs(
+    runs: &[runner::Run],
+    elapsed: Duration,
+    manifest_path: Option<&Path>,
+) -> anyhow::Result<()> {
+    for run in runs.iter().filter(|run| run.outcome.is_failure()) {
+        println!("{run}\n");
+    }
+    println!("{}", runner::Summary { runs, elapsed });
+    if let Some(manifest_path) = manifest_path {
+        Manifest::from_runs(runs).save(manifest_path)?;
+        println!("written: {}", manifest_path.display());
+    }
+    let failures = runs.iter().filter(|run| run.outcome.is_failure()).count();
+    if failures > 0 {
+        return Err(anyhow::anyhow!("{failures} of {} runs failed.", runs.len()));
+    }
+    Ok(())
+}
+
+/// The given years, or all with solutions if none are given.
+fn selected_years(years: Vec<u16>) -> Vec<u16> {
+    if years.is_empty() {
+        registry::years()
+    } else {
+        years
+    }
+}
+
+/// The given profiles, or all of the year if none are given.
+fn selected_profiles(year: u16, profiles: Vec<String>) -> anyhow::Result<Vec<String>> {
+    if profiles.is_empty() {
+        profile::profiles(year)
+    } else {
+        Ok(profiles)
+    }
+}
+
+fn run_vault_command(
+    command: &VaultCommand,
+    key_path: &Path,
+    input_paths: Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    match *command {
+        VaultCommand::Keygen => {
+            if key_path.exists() {
+                return Err(anyhow::anyhow!(
+                    "Keyfile {} does already exist, use `vault rotate` to replace it.",
+                    key_path.display()
+                ));
+            }
+            VaultKey::generate().save(key_path)?;
+            println!("written: {}", key_path.display());
+        }
+        VaultCommand::Encrypt => {
+            let key = VaultKey::load(key_path)?;
+            for input_path in input_paths.iter().filter(|path| path.exists()) {
+                println!(
+                    "encrypted: {}",
+                    vault::encrypt_file(&key, input_path)?.display()
+                );
+            }
+        }
+        VaultCommand::Decrypt => {
+            let key = VaultKey::load(key_path)?;
+            for input_path in input_paths
+                .iter()
+                .filter(|path| vault::vault_path(path).exists())
+            {
+                vault::decrypt_file(&key, input_path)?;
+                println!("decrypted: {}", input_path.display());
+            }
+        }
+        VaultCommand::Rotate => {
+            let old_key = VaultKey::load(key_path)?;
+            let vaulted = input_paths
+                .into_iter()
+                .filter(|path| vault::vault_path(path).exists())
+                .collect::<Vec<_>>();
+            vault::rotate(&old_key, key_path, &vaulted)?;
+            println!(
+                "rotated {} vaults, new key written to {}",
+                vaulted.len(),
+                key_path.display()
+            );
+            if std::env::var_os(vault::KEY_ENV_VAR).is_some() {
+                println!("{} still holds the old key, update it.", vault::KEY_ENV_VAR);
+            }
+        }
+    }
+    Ok(())
+}
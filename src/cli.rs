@@ -0,0 +1,86 @@
+use std::io::BufRead;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use clap::{Args, Parser};
+
+/// Command line arguments every day binary understands.
+#[derive(Debug, Parser)]
+pub struct DayArgs {
+    /// Tracing filter directives per module, e.g. `day05=debug,day11=trace`.
+    ///
+    /// Falls back to the `RUST_LOG` environment variable and then to `warn`.
+    #[arg(long)]
+    pub log: Option<String>,
+    /// Input profile to solve, read from `inputs/<YEAR>/<PROFILE>/` instead of `inputs/<YEAR>/`.
+    #[arg(long, value_parser = crate::profile::parse_name)]
+    pub profile: Option<String>,
+    /// Directory to write text renders of the puzzle state into, e.g. for `aoc report`.
+    #[arg(long)]
+    pub render_dir: Option<PathBuf>,
+}
+
+/// The common arguments together with those only one day understands.
+#[derive(Debug, Parser)]
+struct DayArgsWithExtra<E: Args> {
+    #[command(flatten)]
+    day: DayArgs,
+    #[command(flatten)]
+    extra: E,
+}
+
+impl DayArgs {
+    /// Parses the command line and installs the tracing subscriber accordingly.
+    pub fn init() -> anyhow::Result<Self> {
+        let args = Self::parse();
+        crate::logging::init(args.log.as_deref())?;
+        Ok(args)
+    }
+
+    /// Like [`DayArgs::init`], for days with additional arguments of their own.
+    pub fn init_with<E: Args>() -> anyhow::Result<(Self, E)> {
+        let args = DayArgsWithExtra::<E>::parse();
+        crate::logging::init(args.day.log.as_deref())?;
+        Ok((args.day, args.extra))
+    }
+
+    /// The puzzle input of the selected profile, decrypted from the vault if necessary.
+    pub fn input(&self, year: u16, input_name: &str) -> anyhow::Result<String> {
+        let profile = self
+            .profile
+            .as_deref()
+            .unwrap_or(crate::profile::DEFAULT_PROFILE);
+        crate::profile::load_input(year, profile, input_name)
+    }
+
+    /// Location of the puzzle input of the selected profile, whether it is encrypted or not.
+    pub fn input_path(&self, year: u16, input_name: &str) -> PathBuf {
+        let profile = self
+            .profile
+            .as_deref()
+            .unwrap_or(crate::profile::DEFAULT_PROFILE);
+        crate::profile::input_path(year, profile, input_name)
+    }
+
+    /// The puzzle input of the selected profile as a reader, for days that stream large inputs.
+    pub fn input_reader(&self, year: u16, input_name: &str) -> anyhow::Result<Box<dyn BufRead>> {
+        let profile = self
+            .profile
+            .as_deref()
+            .unwrap_or(crate::profile::DEFAULT_PROFILE);
+        crate::profile::open_input(year, profile, input_name)
+    }
+
+    /// Writes a render to `<RENDER_DIR>/<name>.txt`; it is only drawn if a render dir was given.
+    pub fn render(&self, name: &str, draw: impl FnOnce() -> String) -> anyhow::Result<()> {
+        let Some(ref render_dir) = self.render_dir else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(render_dir)
+            .with_context(|| format!("while creating {}", render_dir.display()))?;
+        let path = render_dir.join(format!("{name}.txt"));
+        std::fs::write(&path, draw())
+            .with_context(|| format!("while writing render {}", path.display()))
+    }
+}
@@ -1,37 +1,426 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+use std::io::BufRead;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("../inputs/day01.input");
+use anyhow::Context;
+
+#[derive(Debug, clap::Args)]
+struct Day01Args {
+    /// Prints statistics of the inventory and the elves carrying the most.
+    #[arg(long)]
+    report: bool,
+}
 
 fn main() -> anyhow::Result<()> {
-    // PART 1 - 9 minutes 10 seconds
-    let mut calories_per_elf = INPUT
-        .split("\n\n")
-        .map(|elves_calories| {
-            elves_calories
-                .lines()
-                .map(u64::from_str)
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .collect::<Result<Vec<Vec<_>>, _>>()?
-        .into_iter()
-        .map(|elves_calories| elves_calories.iter().sum::<u64>())
-        .collect::<Vec<_>>();
+    let (args, day_args) = aoc_2022::cli::DayArgs::init_with::<Day01Args>()?;
 
-    let calories_of_elf_with_maximum = calories_per_elf
-        .iter()
-        .max()
-        .ok_or_else(|| anyhow::anyhow!("No elf found"))?;
+    // PART 1 - 9 minutes 10 seconds
+    let calories_of_elf_with_maximum = tracing::info_span!("part", number = 1)
+        .in_scope(|| calculate_calories_of_elf_with_maximum(args.input_reader(2022, "day01")?))?;
     println!("calories_of_elf_with_maximum: {calories_of_elf_with_maximum}");
 
     // PART 2 - 3 minutes 56 seconds
-    calories_per_elf.sort_unstable();
-    if calories_per_elf.len() < 3 {
-        return Err(anyhow::anyhow!(
-            "Need at minimum 3 elves for the second puzzle part."
-        ));
-    }
-    let calories_of_top_three_elves = calories_per_elf.iter().rev().take(3).sum::<u64>();
+    let calories_of_top_three_elves = tracing::info_span!("part", number = 2)
+        .in_scope(|| calculate_calories_of_top_three_elves(args.input_reader(2022, "day01")?))?;
     println!("calories_of_top_three_elves: {calories_of_top_three_elves}");
 
+    if day_args.report || args.render_dir.is_some() {
+        let inventory = ElfInventory::read(args.input_reader(2022, "day01")?)?;
+        let report = InventoryReport {
+            inventory: &inventory,
+            top: 3,
+            histogram_buckets: 10,
+        };
+        if day_args.report {
+            print!("{report}");
+        }
+        args.render("inventory", || report.to_string())?;
+    }
+
     Ok(())
 }
+
+fn calculate_calories_of_elf_with_maximum(reader: impl BufRead) -> anyhow::Result<u64> {
+    top_k(read_elves(reader), 1)?
+        .first()
+        .map(|elf| elf.calories)
+        .ok_or_else(|| anyhow::anyhow!("No elf found"))
+}
+
+/// The calories of the three elves carrying the most, or of all elves if there are fewer.
+fn calculate_calories_of_top_three_elves(reader: impl BufRead) -> anyhow::Result<u64> {
+    top_k(read_elves(reader), 3)?
+        .iter()
+        .try_fold(0_u64, |sum, elf| sum.checked_add(elf.calories))
+        .ok_or_else(|| anyhow::anyhow!("Sum of the calories of the top three elves overflows."))
+}
+
+/// The `k` greatest items in descending order, keeping only `k` of them in memory at a time.
+fn top_k<T: Ord>(
+    items: impl IntoIterator<Item = anyhow::Result<T>>,
+    k: usize,
+) -> anyhow::Result<Vec<T>> {
+    let mut heap = BinaryHeap::with_capacity(k.saturating_add(1));
+    for item in items {
+        heap.push(Reverse(item?));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    Ok(heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(item)| item)
+        .collect())
+}
+
+/// The food items one elf carries, in the order they were listed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Elf {
+    /// Position of the elf in the input, counted from 0.
+    index: usize,
+    /// Line of the first item, counted from 1.
+    line: usize,
+    items: Vec<u64>,
+    calories: u64,
+}
+
+/// Elves carrying more calories come first; ties go to the elf listed first.
+impl Ord for Elf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.calories
+            .cmp(&other.calories)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Elf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The elves of the input, read one elf at a time; errors name the line they occurred in.
+fn read_elves(reader: impl BufRead) -> impl Iterator<Item = anyhow::Result<Elf>> {
+    let mut lines = aoc_2022::stream::lines(reader)
+        .enumerate()
+        .map(|(index, line)| (index.saturating_add(1), line))
+        .peekable();
+    let mut elf_index = 0_usize;
+    std::iter::from_fn(move || {
+        while lines
+            .next_if(|(_, line)| matches!(*line, Ok(ref line) if line.is_empty()))
+            .is_some()
+        {}
+        let &(first_line, _) = lines.peek()?;
+        let mut elf = Elf {
+            index: elf_index,
+            line: first_line,
+            items: Vec::new(),
+            calories: 0,
+        };
+        elf_index = elf_index.saturating_add(1);
+        while let Some((line_number, line)) =
+            lines.next_if(|(_, line)| !matches!(*line, Ok(ref line) if line.is_empty()))
+        {
+            let item = line.and_then(|line| {
+                u64::from_str(&line).with_context(|| format!("\"{line}\" is not a calorie count"))
+            });
+            let result = item.and_then(|item| {
+                elf.calories = elf.calories.checked_add(item).ok_or_else(|| {
+                    anyhow::anyhow!("Calories of elf #{} overflow.", elf.index.saturating_add(1))
+                })?;
+                elf.items.push(item);
+                Ok(())
+            });
+            if let Err(error) = result {
+                return Some(Err(error.context(format!("in line {line_number}"))));
+            }
+        }
+        Some(Ok(elf))
+    })
+}
+
+/// Every elf with all of their items.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct ElfInventory {
+    elves: Vec<Elf>,
+}
+
+impl ElfInventory {
+    fn read(reader: impl BufRead) -> anyhow::Result<Self> {
+        Ok(Self {
+            elves: read_elves(reader).collect::<anyhow::Result<Vec<_>>>()?,
+        })
+    }
+
+    /// The `k` elves carrying the most calories, in descending order, in O(n log k).
+    fn top_k(&self, k: usize) -> Vec<&Elf> {
+        top_k(self.elves.iter().map(Ok), k).unwrap_or_default()
+    }
+
+    fn statistics(&self) -> Option<CalorieStatistics> {
+        let mut sorted_calories = self
+            .elves
+            .iter()
+            .map(|elf| elf.calories)
+            .collect::<Vec<_>>();
+        sorted_calories.sort_unstable();
+        let total = sorted_calories
+            .iter()
+            .try_fold(0_u64, |sum, &calories| sum.checked_add(calories))?;
+        (!sorted_calories.is_empty()).then_some(CalorieStatistics {
+            sorted_calories,
+            total,
+        })
+    }
+}
+
+impl FromStr for ElfInventory {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::read(s.as_bytes())
+    }
+}
+
+/// Distribution of the calories carried per elf.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct CalorieStatistics {
+    sorted_calories: Vec<u64>,
+    total: u64,
+}
+
+/// Elves carrying `from..=to` calories.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct HistogramBucket {
+    from: u64,
+    to: u64,
+    elves: usize,
+}
+
+impl CalorieStatistics {
+    #[allow(clippy::cast_precision_loss, clippy::as_conversions)]
+    fn mean(&self) -> f64 {
+        self.total as f64 / self.sorted_calories.len() as f64
+    }
+
+    /// The middle value, or the mean of both middle values for an even number of elves.
+    #[allow(clippy::cast_precision_loss, clippy::as_conversions)]
+    fn median(&self) -> f64 {
+        let middle = self.sorted_calories.len() / 2;
+        let upper = self
+            .sorted_calories
+            .get(middle)
+            .copied()
+            .unwrap_or_default();
+        if self.sorted_calories.len() % 2 == 1 {
+            return upper as f64;
+        }
+        let lower = middle
+            .checked_sub(1)
+            .and_then(|index| self.sorted_calories.get(index))
+            .copied()
+            .unwrap_or_default();
+        (lower as f64 + upper as f64) / 2.0
+    }
+
+    /// The smallest calories at least `percent` of the elves carry at most (nearest rank).
+    fn percentile(&self, percent: u8) -> u64 {
+        let count = self.sorted_calories.len();
+        let rank = count
+            .saturating_mul(usize::from(percent.min(100)))
+            .div_ceil(100)
+            .max(1);
+        self.sorted_calories
+            .get(rank.saturating_sub(1))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Splits the range from the fewest to the most calories into equally wide buckets.
+    fn histogram(&self, buckets: usize) -> Vec<HistogramBucket> {
+        let (Some(&minimum), Some(&maximum)) =
+            (self.sorted_calories.first(), self.sorted_calories.last())
+        else {
+            return Vec::new();
+        };
+        let buckets = u64::try_from(buckets.max(1)).unwrap_or(1);
+        let width = (maximum.saturating_sub(minimum) / buckets).saturating_add(1);
+        let mut histogram = Vec::new();
+        let mut from = minimum;
+        while from <= maximum {
+            let to = from.saturating_add(width).saturating_sub(1).min(maximum);
+            let elves = self
+                .sorted_calories
+                .iter()
+                .filter(|&&calories| (from..=to).contains(&calories))
+                .count();
+            histogram.push(HistogramBucket { from, to, elves });
+            let Some(next) = to.checked_add(1) else {
+                break;
+            };
+            from = next;
+        }
+        histogram
+    }
+}
+
+/// Width of the longest bar in the calorie histogram.
+const BAR_WIDTH: usize = 40;
+
+/// Summary of the inventory naming the elves carrying the most.
+struct InventoryReport<'inventory> {
+    inventory: &'inventory ElfInventory,
+    top: usize,
+    histogram_buckets: usize,
+}
+
+impl Display for InventoryReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(statistics) = self.inventory.statistics() else {
+            return writeln!(f, "no elves");
+        };
+        let items = self
+            .inventory
+            .elves
+            .iter()
+            .map(|elf| elf.items.len())
+            .sum::<usize>();
+        writeln!(
+            f,
+            "{} elves carry {items} items with {} calories",
+            self.inventory.elves.len(),
+            statistics.total
+        )?;
+        writeln!(
+            f,
+            "mean {:.1}, median {:.1}, 90th percentile {}",
+            statistics.mean(),
+            statistics.median(),
+            statistics.percentile(90)
+        )?;
+        writeln!(f, "carrying the most:")?;
+        for (rank, elf) in self.inventory.top_k(self.top).into_iter().enumerate() {
+            writeln!(
+                f,
+                "  {}. elf #{} (line {}): {} calories in {} items",
+                rank.saturating_add(1),
+                elf.index.saturating_add(1),
+                elf.line,
+                elf.calories,
+                elf.items.len()
+            )?;
+        }
+        writeln!(f, "calories per elf:")?;
+        let histogram = statistics.histogram(self.histogram_buckets);
+        let most_elves = histogram
+            .iter()
+            .map(|bucket| bucket.elves)
+            .max()
+            .unwrap_or(1);
+        for bucket in histogram {
+            let bar = bucket.elves.saturating_mul(BAR_WIDTH) / most_elves.max(1);
+            writeln!(
+                f,
+                "  {:>8} - {:>8} | {:<BAR_WIDTH$} {}",
+                bucket.from,
+                bucket.to,
+                "#".repeat(bar),
+                bucket.elves
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day01"],
+        test_part_1_default: "calories_of_elf_with_maximum" => |input| {
+            calculate_calories_of_elf_with_maximum(input.as_bytes())
+        },
+        test_part_2_default: "calories_of_top_three_elves" => |input| {
+            calculate_calories_of_top_three_elves(input.as_bytes())
+        },
+    }
+
+    const SAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+
+    #[test]
+    fn test_top_k_keeps_items_and_original_index() -> anyhow::Result<()> {
+        // Arrange
+        let inventory = ElfInventory::from_str(SAMPLE)?;
+
+        // Act
+        let top = inventory.top_k(2);
+
+        // Assert
+        let summary = top
+            .iter()
+            .map(|elf| (elf.index, elf.line, elf.items.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![(3, 10, vec![7000, 8000, 9000]), (2, 7, vec![5000, 6000])]
+        );
+        assert_eq!(inventory.top_k(10).len(), 5);
+        assert!(inventory.top_k(0).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_three_with_fewer_elves() -> anyhow::Result<()> {
+        // Act
+        let calories = calculate_calories_of_top_three_elves("1\n2\n\n3\n".as_bytes())?;
+
+        // Assert
+        assert_eq!(calories, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_statistics() -> anyhow::Result<()> {
+        // Arrange
+        let inventory = ElfInventory::from_str(SAMPLE)?;
+
+        // Act
+        let statistics = inventory
+            .statistics()
+            .ok_or_else(|| anyhow::anyhow!("No statistics."))?;
+
+        // Assert
+        assert!((statistics.mean() - 11_000.0).abs() < f64::EPSILON);
+        assert!((statistics.median() - 10_000.0).abs() < f64::EPSILON);
+        assert_eq!(statistics.percentile(80), 11_000);
+        assert_eq!(statistics.percentile(100), 24_000);
+        let histogram = statistics.histogram(2);
+        assert_eq!(
+            histogram
+                .iter()
+                .map(|bucket| bucket.elves)
+                .collect::<Vec<_>>(),
+            vec![4, 1]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_names_line() {
+        // Act
+        let error = ElfInventory::from_str("1000\n\n2000\n2k00\n");
+
+        // Assert
+        assert!(matches!(error, Err(error) if error.to_string() == "in line 4"));
+    }
+}
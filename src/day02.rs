@@ -1,59 +1,611 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("../inputs/day02.input");
+use anyhow::Context;
+
+/// Rules of the game the strategy guide of the puzzle is written for.
+const ROCK_PAPER_SCISSORS: &str = include_str!("../data/day02/rock-paper-scissors.rules");
+
+#[derive(Debug, clap::Args)]
+struct Day02Args {
+    /// Rules file of another game, like `data/day02/rock-paper-scissors-lizard-spock.rules`.
+    #[arg(long)]
+    rules: Option<PathBuf>,
+    /// Prints how every reading of the second column would score, and the score of perfect play.
+    #[arg(long)]
+    analyze: bool,
+    /// Simulates this many tournaments against an opponent modelled on the guide and prints the
+    /// expected score of several strategies.
+    #[arg(long)]
+    simulate: Option<u64>,
+    /// Seed of the simulated tournaments.
+    #[arg(long, default_value_t = 2022)]
+    seed: u64,
+}
 
 fn main() -> anyhow::Result<()> {
+    let (args, day_args) = aoc_2022::cli::DayArgs::init_with::<Day02Args>()?;
+    let rules = match day_args.rules {
+        Some(path) => GameRules::load(&path)?,
+        None => GameRules::from_str(ROCK_PAPER_SCISSORS)?,
+    };
+
     // PART 1 - 20 minutes 45 seconds
-    let score_by_guesswork = RockPaperScissorsMatch::from_guess(INPUT)?.score()?;
+    let score_by_guesswork = tracing::info_span!("part", number = 1)
+        .in_scope(|| calculate_score_by_guesswork(&rules, args.input_reader(2022, "day02")?))?;
     println!("score_by_guesswork: {score_by_guesswork}");
 
     // PART 2 - 11 minutes 2 seconds
-    let score_by_elf_explanation = RockPaperScissorsMatch::from_elf_information(INPUT)?.score()?;
+    let score_by_elf_explanation = tracing::info_span!("part", number = 2).in_scope(|| {
+        calculate_score_by_elf_explanation(&rules, args.input_reader(2022, "day02")?)
+    })?;
     println!("score_by_elf_explanation: {score_by_elf_explanation}");
 
+    if day_args.analyze || args.render_dir.is_some() {
+        let analysis = GuideAnalysis::analyze(
+            &rules,
+            &GuideTally::read(&rules, args.input_reader(2022, "day02")?)?,
+        )?;
+        if day_args.analyze {
+            print!("{analysis}");
+        }
+        args.render("guide-analysis", || analysis.to_string())?;
+    }
+
+    if let Some(tournaments) = day_args.simulate {
+        let model = OpponentModel::learn(&rules, args.input_reader(2022, "day02")?)?;
+        print!(
+            "{}",
+            Simulation::run(&rules, &model, tournaments, day_args.seed)?
+        );
+    }
+
     Ok(())
 }
 
-struct RockPaperScissorsMatch {
-    rounds: Vec<RockPaperScissorsRound>,
+fn calculate_score_by_guesswork(rules: &GameRules, reader: impl BufRead) -> anyhow::Result<u64> {
+    score_rounds(rules, reader, |line| {
+        RockPaperScissorsRound::parse_guesswork(rules, line)
+    })
 }
 
-impl RockPaperScissorsMatch {
-    fn from_guess(input: &str) -> Result<Self, anyhow::Error> {
-        Ok(Self {
-            rounds: input
-                .lines()
-                .map(RockPaperScissorsRound::from_str)
-                .collect::<Result<Vec<_>, _>>()?,
+fn calculate_score_by_elf_explanation(
+    rules: &GameRules,
+    reader: impl BufRead,
+) -> anyhow::Result<u64> {
+    score_rounds(rules, reader, |line| {
+        RockPaperScissorsElfExplanation::parse(rules, line)?.round(rules)
+    })
+}
+
+/// Sums the scores of the rounds while reading them line by line.
+fn score_rounds(
+    rules: &GameRules,
+    reader: impl BufRead,
+    parse_round: impl Fn(&str) -> anyhow::Result<RockPaperScissorsRound>,
+) -> anyhow::Result<u64> {
+    aoc_2022::stream::lines(reader)
+        .enumerate()
+        .try_fold(0_u64, |score, (index, line)| {
+            let round_score = rules
+                .score(parse_round(&line?).with_context(|| format!("in line #{index}"))?)
+                .ok_or_else(|| anyhow::anyhow!("Could not calculate round score."))?;
+            score
+                .checked_add(round_score)
+                .ok_or_else(|| anyhow::anyhow!("Score of the match is too large."))
         })
+}
+
+/// How often each symbol of the second column was written against each enemy sign.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct GuideTally {
+    counts: BTreeMap<(usize, String), u64>,
+}
+
+impl GuideTally {
+    fn read(rules: &GameRules, reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut counts = BTreeMap::new();
+        for (index, line) in aoc_2022::stream::lines(reader).enumerate() {
+            let line = line?;
+            let (enemy_symbol, symbol) =
+                split_columns(&line).with_context(|| format!("in line #{index}"))?;
+            let enemy_selection = rules
+                .enemy_sign(enemy_symbol)
+                .with_context(|| format!("in line #{index}"))?;
+            let count: &mut u64 = counts
+                .entry((enemy_selection.0, symbol.to_owned()))
+                .or_default();
+            *count = count.saturating_add(1);
+        }
+        Ok(Self { counts })
     }
 
-    fn from_elf_information(input: &str) -> Result<Self, anyhow::Error> {
-        Ok(Self {
-            rounds: input
-                .lines()
-                .map(|line| {
-                    RockPaperScissorsElfExplanation::from_str(line)
-                        .map(RockPaperScissorsRound::from)
+    /// The distinct symbols of the second column, sorted.
+    fn symbols(&self) -> Vec<&str> {
+        let mut symbols = self
+            .counts
+            .keys()
+            .map(|(_, symbol)| symbol.as_str())
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols
+    }
+
+    /// Sums the score of every round, with the own sign chosen from the enemy sign and the symbol.
+    fn score(
+        &self,
+        rules: &GameRules,
+        own_selection: impl Fn(RockPaperScissorsSign, &str) -> Option<RockPaperScissorsSign>,
+    ) -> Option<u64> {
+        self.counts
+            .iter()
+            .try_fold(0_u64, |score, (&(enemy_selection, ref symbol), &count)| {
+                let enemy_selection = RockPaperScissorsSign(enemy_selection);
+                let round = RockPaperScissorsRound {
+                    enemy_selection,
+                    own_selection: own_selection(enemy_selection, symbol)?,
+                };
+                score.checked_add(rules.score(round)?.checked_mul(count)?)
+            })
+    }
+}
+
+/// A way to read the second column of the strategy guide.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Interpretation {
+    Signs(Vec<(String, RockPaperScissorsSign)>),
+    Winners(Vec<(String, RockPaperScissorsWinner)>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct RankedInterpretation {
+    interpretation: Interpretation,
+    score: u64,
+}
+
+/// Every reading of the second column of the guide ranked by its score, and the score of playing
+/// the best sign against every enemy sign.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct GuideAnalysis<'rules> {
+    rules: &'rules GameRules,
+    ranked: Vec<RankedInterpretation>,
+    perfect_score: u64,
+}
+
+impl<'rules> GuideAnalysis<'rules> {
+    /// Most readings of either kind to score, as their number grows exponentially with the symbols.
+    const MAX_READINGS: usize = 100_000;
+
+    fn analyze(rules: &'rules GameRules, tally: &GuideTally) -> anyhow::Result<Self> {
+        let symbols = tally.symbols();
+        let winners = [
+            RockPaperScissorsWinner::Enemy,
+            RockPaperScissorsWinner::Draw,
+            RockPaperScissorsWinner::Myself,
+        ];
+        for targets in [rules.signs.len(), winners.len()] {
+            let readings = u32::try_from(symbols.len())
+                .ok()
+                .and_then(|exponent| targets.checked_pow(exponent));
+            if readings.is_none_or(|readings| readings > Self::MAX_READINGS) {
+                return Err(anyhow::anyhow!(
+                    "The second column has {} symbols, too many to read them in every way.",
+                    symbols.len()
+                ));
+            }
+        }
+        let sign_readings = mappings(symbols.len(), rules.signs.len())
+            .into_iter()
+            .map(|targets| {
+                Interpretation::Signs(
+                    symbols
+                        .iter()
+                        .zip(targets)
+                        .map(|(&symbol, sign)| (symbol.to_owned(), RockPaperScissorsSign(sign)))
+                        .collect(),
+                )
+            });
+        let winner_readings = mappings(symbols.len(), winners.len())
+            .into_iter()
+            .map(|targets| {
+                Interpretation::Winners(
+                    symbols
+                        .iter()
+                        .zip(targets)
+                        .filter_map(|(&symbol, winner)| {
+                            Some((symbol.to_owned(), *winners.get(winner)?))
+                        })
+                        .collect(),
+                )
+            });
+        let mut ranked = sign_readings
+            .chain(winner_readings)
+            .map(|interpretation| {
+                let score = match interpretation {
+                    Interpretation::Signs(ref mapping) => {
+                        tally.score(rules, |_, symbol| lookup(mapping, symbol))
+                    }
+                    Interpretation::Winners(ref mapping) => {
+                        tally.score(rules, |enemy_selection, symbol| {
+                            rules.sign_for_winner(enemy_selection, lookup(mapping, symbol)?)
+                        })
+                    }
+                };
+                Ok(RankedInterpretation {
+                    score: score.ok_or_else(|| {
+                        anyhow::anyhow!("Could not score interpretation {interpretation:?}.")
+                    })?,
+                    interpretation,
                 })
-                .collect::<Result<Vec<_>, _>>()?,
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        ranked.sort_by_key(|ranked| std::cmp::Reverse(ranked.score));
+        let perfect_score = tally
+            .score(rules, |enemy_selection, _| {
+                rules.best_reply(enemy_selection)
+            })
+            .ok_or_else(|| anyhow::anyhow!("Could not score perfect play."))?;
+        Ok(Self {
+            rules,
+            ranked,
+            perfect_score,
+        })
+    }
+
+    /// The part that reads the second column this way, if any.
+    fn part_of(&self, interpretation: &Interpretation) -> Option<&'static str> {
+        match *interpretation {
+            Interpretation::Signs(ref mapping) => mapping
+                .iter()
+                .all(|&(ref symbol, sign)| self.rules.own_sign(symbol).ok() == Some(sign))
+                .then_some("part 1"),
+            Interpretation::Winners(ref mapping) => mapping
+                .iter()
+                .all(|&(ref symbol, winner)| self.rules.winner(symbol).ok() == Some(winner))
+                .then_some("part 2"),
+        }
+    }
+}
+
+impl Display for GuideAnalysis<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "perfect play against the enemy column: {}",
+            self.perfect_score
+        )?;
+        writeln!(f, "{:>4} {:>8}  second column", "rank", "score")?;
+        for (rank, ranked) in self.ranked.iter().enumerate() {
+            let mapping = match ranked.interpretation {
+                Interpretation::Signs(ref mapping) => mapping
+                    .iter()
+                    .map(|&(ref symbol, sign)| format!("{symbol}={}", self.rules.sign_name(sign)))
+                    .collect::<Vec<_>>(),
+                Interpretation::Winners(ref mapping) => mapping
+                    .iter()
+                    .map(|&(ref symbol, winner)| format!("{symbol}={winner}"))
+                    .collect::<Vec<_>>(),
+            };
+            write!(
+                f,
+                "{:>4} {:>8}  {}",
+                rank.saturating_add(1),
+                ranked.score,
+                mapping.join(" ")
+            )?;
+            if let Some(part) = self.part_of(&ranked.interpretation) {
+                write!(f, " ({part})")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn lookup<T: Copy>(mapping: &[(String, T)], symbol: &str) -> Option<T> {
+    mapping
+        .iter()
+        .find(|&(mapped, _)| mapped == symbol)
+        .map(|&(_, target)| target)
+}
+
+/// Every way to assign each of `count` items one of `targets` targets, by target index.
+fn mappings(count: usize, targets: usize) -> Vec<Vec<usize>> {
+    if count == 0 {
+        return vec![Vec::new()];
+    }
+    mappings(count.saturating_sub(1), targets)
+        .into_iter()
+        .flat_map(|prefix| {
+            (0..targets).map(move |target| {
+                let mut mapping = prefix.clone();
+                mapping.push(target);
+                mapping
+            })
         })
+        .collect()
+}
+
+/// What the strategy guide tells about the opponent: how often it plays each sign, which sign
+/// follows which, and how the guide answers each sign.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct OpponentModel {
+    /// `signs[sign]`
+    signs: Vec<u64>,
+    /// `transitions[previous][next]`
+    transitions: Vec<Vec<u64>>,
+    /// `guide_replies[enemy][own]`, with the second column read as the outcome.
+    guide_replies: Vec<Vec<u64>>,
+    rounds: u64,
+}
+
+impl OpponentModel {
+    fn learn(rules: &GameRules, reader: impl BufRead) -> anyhow::Result<Self> {
+        let sign_count = rules.signs.len();
+        let mut model = Self {
+            signs: vec![0; sign_count],
+            transitions: vec![vec![0; sign_count]; sign_count],
+            guide_replies: vec![vec![0; sign_count]; sign_count],
+            rounds: 0,
+        };
+        let mut previous: Option<RockPaperScissorsSign> = None;
+        for (index, line) in aoc_2022::stream::lines(reader).enumerate() {
+            let line = line?;
+            let round = RockPaperScissorsElfExplanation::parse(rules, &line)
+                .and_then(|explanation| explanation.round(rules))
+                .with_context(|| format!("in line #{index}"))?;
+            let enemy = round.enemy_selection.0;
+            increment(model.signs.get_mut(enemy));
+            if let Some(previous) = previous {
+                increment(
+                    model
+                        .transitions
+                        .get_mut(previous.0)
+                        .and_then(|next| next.get_mut(enemy)),
+                );
+            }
+            increment(
+                model
+                    .guide_replies
+                    .get_mut(enemy)
+                    .and_then(|replies| replies.get_mut(round.own_selection.0)),
+            );
+            model.rounds = model.rounds.saturating_add(1);
+            previous = Some(round.enemy_selection);
+        }
+        Ok(model)
+    }
+
+    /// The sign most often played after the previous one, or overall for the first round.
+    fn predict(&self, previous: Option<RockPaperScissorsSign>) -> Option<RockPaperScissorsSign> {
+        let counts = self.next_counts(previous);
+        most_frequent(counts)
     }
 
-    fn score(&self) -> Result<u64, anyhow::Error> {
-        Ok(self
-            .rounds
+    /// Draws the next sign of the opponent from the learned transitions.
+    fn sample(
+        &self,
+        rng: &mut fastrand::Rng,
+        previous: Option<RockPaperScissorsSign>,
+    ) -> Option<RockPaperScissorsSign> {
+        let counts = self.next_counts(previous);
+        let total = counts.iter().sum::<u64>();
+        if total == 0 {
+            return None;
+        }
+        let mut drawn = rng.u64(..total);
+        counts
             .iter()
-            .map(|round| {
-                round
-                    .score()
-                    .map(u64::from)
-                    .ok_or_else(|| anyhow::anyhow!("Could not calculate round score."))
+            .position(|&count| {
+                if drawn < count {
+                    true
+                } else {
+                    drawn = drawn.saturating_sub(count);
+                    false
+                }
             })
-            .collect::<Result<Vec<_>, _>>()?
+            .map(RockPaperScissorsSign)
+    }
+
+    /// Transitions from the previous sign, falling back to the overall distribution if the
+    /// previous sign was never followed by another.
+    fn next_counts(&self, previous: Option<RockPaperScissorsSign>) -> &[u64] {
+        previous
+            .and_then(|previous| self.transitions.get(previous.0))
+            .filter(|counts| counts.iter().any(|&count| count > 0))
+            .unwrap_or(&self.signs)
+    }
+}
+
+fn increment(count: Option<&mut u64>) {
+    if let Some(count) = count {
+        *count = count.saturating_add(1);
+    }
+}
+
+fn most_frequent(counts: &[u64]) -> Option<RockPaperScissorsSign> {
+    counts
+        .iter()
+        .enumerate()
+        .filter(|&(_, &count)| count > 0)
+        .max_by_key(|&(_, &count)| count)
+        .map(|(sign, _)| RockPaperScissorsSign(sign))
+}
+
+/// How to pick the own sign in a simulated tournament.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Strategy {
+    /// Always the same sign.
+    Fixed(RockPaperScissorsSign),
+    /// The best reply to the sign the opponent most likely plays after its previous one.
+    CounterMarkov,
+    /// The reply the guide most often gives to the sign the opponent plays.
+    Guide,
+}
+
+impl Strategy {
+    fn all(rules: &GameRules) -> Vec<Self> {
+        (0..rules.signs.len())
+            .map(|sign| Self::Fixed(RockPaperScissorsSign(sign)))
+            .chain([Self::CounterMarkov, Self::Guide])
+            .collect()
+    }
+
+    fn name(self, rules: &GameRules) -> String {
+        match self {
+            Self::Fixed(sign) => format!("always {}", rules.sign_name(sign)),
+            Self::CounterMarkov => "counter-markov".to_owned(),
+            Self::Guide => "guide".to_owned(),
+        }
+    }
+
+    fn choose(
+        self,
+        rules: &GameRules,
+        model: &OpponentModel,
+        previous: Option<RockPaperScissorsSign>,
+        enemy_selection: RockPaperScissorsSign,
+    ) -> Option<RockPaperScissorsSign> {
+        match self {
+            Self::Fixed(sign) => Some(sign),
+            Self::CounterMarkov => rules.best_reply(model.predict(previous)?),
+            Self::Guide => most_frequent(model.guide_replies.get(enemy_selection.0)?),
+        }
+    }
+}
+
+/// Mean and variance of the total score of one strategy over all simulated tournaments.
+#[derive(Debug, Clone, PartialEq)]
+struct StrategyOutcome {
+    strategy: Strategy,
+    mean: f64,
+    variance: f64,
+}
+
+/// Tournaments as long as the guide, played by every strategy against the same opponent moves.
+#[derive(Debug, Clone, PartialEq)]
+struct Simulation<'rules> {
+    rules: &'rules GameRules,
+    tournaments: u64,
+    rounds: u64,
+    seed: u64,
+    outcomes: Vec<StrategyOutcome>,
+}
+
+impl<'rules> Simulation<'rules> {
+    fn run(
+        rules: &'rules GameRules,
+        model: &OpponentModel,
+        tournaments: u64,
+        seed: u64,
+    ) -> anyhow::Result<Self> {
+        let strategies = Strategy::all(rules);
+        let mut totals = vec![Vec::new(); strategies.len()];
+        let mut rng = fastrand::Rng::with_seed(seed);
+        for _ in 0..tournaments {
+            let mut scores = vec![0_u64; strategies.len()];
+            let mut previous = None;
+            for _ in 0..model.rounds {
+                let enemy_selection = model
+                    .sample(&mut rng, previous)
+                    .ok_or_else(|| anyhow::anyhow!("The guide has no opponent signs."))?;
+                for (&strategy, score) in strategies.iter().zip(scores.iter_mut()) {
+                    let round = RockPaperScissorsRound {
+                        enemy_selection,
+                        own_selection: strategy
+                            .choose(rules, model, previous, enemy_selection)
+                            .ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "Strategy {} has no sign to play.",
+                                    strategy.name(rules)
+                                )
+                            })?,
+                    };
+                    *score = rules
+                        .score(round)
+                        .and_then(|round_score| score.checked_add(round_score))
+                        .ok_or_else(|| anyhow::anyhow!("Could not score round {round:?}."))?;
+                }
+                previous = Some(enemy_selection);
+            }
+            for (total, score) in totals.iter_mut().zip(scores) {
+                total.push(score);
+            }
+        }
+        let mut outcomes = strategies
             .into_iter()
-            .sum::<u64>())
+            .zip(totals)
+            .map(|(strategy, scores)| {
+                let (mean, variance) = mean_and_variance(&scores);
+                StrategyOutcome {
+                    strategy,
+                    mean,
+                    variance,
+                }
+            })
+            .collect::<Vec<_>>();
+        outcomes.sort_by(|a, b| b.mean.total_cmp(&a.mean));
+        Ok(Self {
+            rules,
+            tournaments,
+            rounds: model.rounds,
+            seed,
+            outcomes,
+        })
+    }
+}
+
+impl Display for Simulation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} tournaments of {} rounds, seed {}",
+            self.tournaments, self.rounds, self.seed
+        )?;
+        writeln!(
+            f,
+            "{:<16} {:>12} {:>12} {:>12}",
+            "strategy", "mean", "variance", "std dev"
+        )?;
+        for outcome in &self.outcomes {
+            writeln!(
+                f,
+                "{:<16} {:>12.1} {:>12.1} {:>12.1}",
+                outcome.strategy.name(self.rules),
+                outcome.mean,
+                outcome.variance,
+                outcome.variance.sqrt()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Population mean and variance, zero for no values.
+#[allow(clippy::cast_precision_loss, clippy::as_conversions)]
+fn mean_and_variance(values: &[u64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
     }
+    let count = values.len() as f64;
+    let mean = values.iter().map(|&value| value as f64).sum::<f64>() / count;
+    let variance = values
+        .iter()
+        .map(|&value| (value as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    (mean, variance)
+}
+
+/// Splits a line of the strategy guide into its two columns.
+fn split_columns(line: &str) -> anyhow::Result<(&str, &str)> {
+    line.split_once(' ')
+        .filter(|&(_, second)| !second.contains(' '))
+        .ok_or_else(|| anyhow::anyhow!("Expected two columns in \"{line}\"."))
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -62,32 +614,28 @@ struct RockPaperScissorsElfExplanation {
     target_winner: RockPaperScissorsWinner,
 }
 
-impl FromStr for RockPaperScissorsElfExplanation {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let selections: [&str; 2] =
-            s.split(' ')
-                .collect::<Vec<_>>()
-                .try_into()
-                .map_err(|a: Vec<&str>| {
-                    anyhow::anyhow!(
-                        "Failed to convert Vec with len {} to array with len 2.",
-                        a.len()
-                    )
-                })?;
+impl RockPaperScissorsElfExplanation {
+    fn parse(rules: &GameRules, line: &str) -> anyhow::Result<Self> {
+        let (enemy_symbol, winner_symbol) = split_columns(line)?;
         Ok(Self {
-            enemy_selection: RockPaperScissorsSign::from_str(selections[0])?,
-            target_winner: match selections[1] {
-                "X" => RockPaperScissorsWinner::Enemy,
-                "Y" => RockPaperScissorsWinner::Draw,
-                "Z" => RockPaperScissorsWinner::Myself,
-                other => {
-                    return Err(anyhow::anyhow!(
-                        "Unexpected second proposition string found \"{other}\""
-                    ))
-                }
-            },
+            enemy_selection: rules.enemy_sign(enemy_symbol)?,
+            target_winner: rules.winner(winner_symbol)?,
+        })
+    }
+
+    fn round(self, rules: &GameRules) -> anyhow::Result<RockPaperScissorsRound> {
+        let own_selection = rules
+            .sign_for_winner(self.enemy_selection, self.target_winner)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No sign leads to {:?} against {}.",
+                    self.target_winner,
+                    rules.sign_name(self.enemy_selection)
+                )
+            })?;
+        Ok(RockPaperScissorsRound {
+            enemy_selection: self.enemy_selection,
+            own_selection,
         })
     }
 }
@@ -99,123 +647,455 @@ struct RockPaperScissorsRound {
 }
 
 impl RockPaperScissorsRound {
-    fn winner(self) -> RockPaperScissorsWinner {
-        use RockPaperScissorsSign::{Paper, Rock, Scissors};
-        use RockPaperScissorsWinner::{Draw, Enemy, Myself};
-        match self.own_selection {
-            Rock => match self.enemy_selection {
-                Rock => Draw,
-                Paper => Enemy,
-                Scissors => Myself,
-            },
-            Paper => match self.enemy_selection {
-                Rock => Myself,
-                Paper => Draw,
-                Scissors => Enemy,
-            },
-            Scissors => match self.enemy_selection {
-                Rock => Enemy,
-                Paper => Myself,
-                Scissors => Draw,
-            },
-        }
-    }
-
-    fn score(self) -> Option<u8> {
-        self.own_selection
-            .selection_score()
-            .checked_add(self.winner().score())
-    }
-}
-
-impl FromStr for RockPaperScissorsRound {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let selections: [RockPaperScissorsSign; 2] = s
-            .split(' ')
-            .map(RockPaperScissorsSign::from_str)
-            .collect::<Result<Vec<_>, _>>()?
-            .try_into()
-            .map_err(|vec: Vec<_>| {
-                anyhow::anyhow!(
-                    "Failed to convert Vec with len {} to array with len 2.",
-                    vec.len()
-                )
-            })?;
+    fn parse_guesswork(rules: &GameRules, line: &str) -> anyhow::Result<Self> {
+        let (enemy_symbol, own_symbol) = split_columns(line)?;
         Ok(Self {
-            enemy_selection: selections[0],
-            own_selection: selections[1],
+            enemy_selection: rules.enemy_sign(enemy_symbol)?,
+            own_selection: rules.own_sign(own_symbol)?,
         })
     }
 }
 
-impl From<RockPaperScissorsElfExplanation> for RockPaperScissorsRound {
-    fn from(value: RockPaperScissorsElfExplanation) -> Self {
-        use RockPaperScissorsSign::{Paper, Rock, Scissors};
-        use RockPaperScissorsWinner::{Draw, Enemy, Myself};
-        Self {
-            enemy_selection: value.enemy_selection,
-            own_selection: match value.target_winner {
-                Draw => value.enemy_selection,
-                Myself => match value.enemy_selection {
-                    Rock => Paper,
-                    Paper => Scissors,
-                    Scissors => Rock,
-                },
-                Enemy => match value.enemy_selection {
-                    Rock => Scissors,
-                    Paper => Rock,
-                    Scissors => Paper,
-                },
-            },
+/// A sign of the game, as index into the signs of its [`GameRules`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct RockPaperScissorsSign(usize);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum RockPaperScissorsWinner {
+    Enemy,
+    Draw,
+    Myself,
+}
+
+impl Display for RockPaperScissorsWinner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            Self::Enemy => "loss",
+            Self::Draw => "draw",
+            Self::Myself => "win",
+        })
+    }
+}
+
+impl FromStr for RockPaperScissorsWinner {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "loss" => Ok(Self::Enemy),
+            "draw" => Ok(Self::Draw),
+            "win" => Ok(Self::Myself),
+            _ => Err(anyhow::anyhow!(
+                "Outcome \"{s}\" is neither loss, draw nor win."
+            )),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-enum RockPaperScissorsSign {
-    Rock,
-    Paper,
-    Scissors,
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct SignRule {
+    name: String,
+    score: u64,
+    enemy_symbol: String,
+    own_symbol: String,
 }
 
-impl RockPaperScissorsSign {
-    fn selection_score(self) -> u8 {
-        match self {
-            Self::Rock => 1,
-            Self::Paper => 2,
-            Self::Scissors => 3,
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct WinnerRule {
+    winner: RockPaperScissorsWinner,
+    score: u64,
+    symbol: String,
+}
+
+/// Signs, dominance relation, scores and symbols of a rock-paper-scissors like game.
+///
+/// Written one rule per line: `sign <name> <score> <enemy symbol> <own symbol>`,
+/// `beats <winner> <loser>...` and `outcome <loss|draw|win> <score> <symbol>`. Signs neither
+/// beating the other draw.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct GameRules {
+    signs: Vec<SignRule>,
+    /// `beats[winner][loser]`
+    beats: Vec<Vec<bool>>,
+    winners: Vec<WinnerRule>,
+}
+
+impl GameRules {
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("while reading rules {}", path.display()))?
+            .parse()
+            .with_context(|| format!("while parsing rules {}", path.display()))
+    }
+
+    fn sign_name(&self, sign: RockPaperScissorsSign) -> &str {
+        self.signs.get(sign.0).map_or("?", |rule| &rule.name)
+    }
+
+    fn enemy_sign(&self, symbol: &str) -> anyhow::Result<RockPaperScissorsSign> {
+        self.signs
+            .iter()
+            .position(|rule| rule.enemy_symbol == symbol)
+            .map(RockPaperScissorsSign)
+            .ok_or_else(|| anyhow::anyhow!("Unexpected enemy sign \"{symbol}\" found"))
+    }
+
+    fn own_sign(&self, symbol: &str) -> anyhow::Result<RockPaperScissorsSign> {
+        self.signs
+            .iter()
+            .position(|rule| rule.own_symbol == symbol)
+            .map(RockPaperScissorsSign)
+            .ok_or_else(|| anyhow::anyhow!("Unexpected own sign \"{symbol}\" found"))
+    }
+
+    fn winner(&self, symbol: &str) -> anyhow::Result<RockPaperScissorsWinner> {
+        self.winners
+            .iter()
+            .find(|rule| rule.symbol == symbol)
+            .map(|rule| rule.winner)
+            .ok_or_else(|| anyhow::anyhow!("Unexpected outcome \"{symbol}\" found"))
+    }
+
+    fn beats(&self, winner: RockPaperScissorsSign, loser: RockPaperScissorsSign) -> bool {
+        self.beats
+            .get(winner.0)
+            .and_then(|losers| losers.get(loser.0))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    fn round_winner(&self, round: RockPaperScissorsRound) -> RockPaperScissorsWinner {
+        if self.beats(round.own_selection, round.enemy_selection) {
+            RockPaperScissorsWinner::Myself
+        } else if self.beats(round.enemy_selection, round.own_selection) {
+            RockPaperScissorsWinner::Enemy
+        } else {
+            RockPaperScissorsWinner::Draw
         }
     }
+
+    /// The own sign leading to the outcome, preferring the highest scoring one if there are several.
+    fn sign_for_winner(
+        &self,
+        enemy_selection: RockPaperScissorsSign,
+        target_winner: RockPaperScissorsWinner,
+    ) -> Option<RockPaperScissorsSign> {
+        (0..self.signs.len())
+            .map(RockPaperScissorsSign)
+            .filter(|&own_selection| {
+                self.round_winner(RockPaperScissorsRound {
+                    enemy_selection,
+                    own_selection,
+                }) == target_winner
+            })
+            .max_by_key(|sign| self.signs.get(sign.0).map(|rule| rule.score))
+    }
+
+    /// The own sign scoring most against the enemy sign.
+    fn best_reply(&self, enemy_selection: RockPaperScissorsSign) -> Option<RockPaperScissorsSign> {
+        (0..self.signs.len())
+            .map(RockPaperScissorsSign)
+            .max_by_key(|&own_selection| {
+                self.score(RockPaperScissorsRound {
+                    enemy_selection,
+                    own_selection,
+                })
+            })
+    }
+
+    fn score(&self, round: RockPaperScissorsRound) -> Option<u64> {
+        let winner = self.round_winner(round);
+        let winner_score = self
+            .winners
+            .iter()
+            .find(|rule| rule.winner == winner)?
+            .score;
+        self.signs
+            .get(round.own_selection.0)?
+            .score
+            .checked_add(winner_score)
+    }
+
+    fn sign_by_name(&self, name: &str) -> anyhow::Result<usize> {
+        self.signs
+            .iter()
+            .position(|rule| rule.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Sign \"{name}\" is not defined before."))
+    }
+
+    fn add_beats(&mut self, winner: &str, losers: &[&str]) -> anyhow::Result<()> {
+        let winner = self.sign_by_name(winner)?;
+        let sign_count = self.signs.len();
+        self.beats.resize(sign_count, Vec::new());
+        for loser in losers {
+            let loser = self.sign_by_name(loser)?;
+            if loser == winner {
+                return Err(anyhow::anyhow!("A sign cannot beat itself."));
+            }
+            if let Some(losers) = self.beats.get_mut(winner) {
+                losers.resize(sign_count, false);
+                if let Some(beats) = losers.get_mut(loser) {
+                    *beats = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.signs.is_empty() {
+            return Err(anyhow::anyhow!("No signs are defined."));
+        }
+        for (index, rule) in self.signs.iter().enumerate() {
+            let others = self.signs.iter().skip(index.saturating_add(1));
+            for other in others {
+                if other.name == rule.name
+                    || other.enemy_symbol == rule.enemy_symbol
+                    || other.own_symbol == rule.own_symbol
+                {
+                    return Err(anyhow::anyhow!(
+                        "Signs {} and {} share a name or symbol.",
+                        rule.name,
+                        other.name
+                    ));
+                }
+            }
+        }
+        for (winner, losers) in self.beats.iter().enumerate() {
+            for (loser, &beats) in losers.iter().enumerate() {
+                if beats && self.beats(RockPaperScissorsSign(loser), RockPaperScissorsSign(winner))
+                {
+                    return Err(anyhow::anyhow!(
+                        "Signs {} and {} beat each other.",
+                        self.sign_name(RockPaperScissorsSign(winner)),
+                        self.sign_name(RockPaperScissorsSign(loser))
+                    ));
+                }
+            }
+        }
+        for winner in [
+            RockPaperScissorsWinner::Enemy,
+            RockPaperScissorsWinner::Draw,
+            RockPaperScissorsWinner::Myself,
+        ] {
+            let count = self
+                .winners
+                .iter()
+                .filter(|rule| rule.winner == winner)
+                .count();
+            if count != 1 {
+                return Err(anyhow::anyhow!(
+                    "Outcome {winner:?} is defined {count} times instead of once."
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
-impl FromStr for RockPaperScissorsSign {
+impl FromStr for GameRules {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "A" | "X" => Ok(Self::Rock),
-            "B" | "Y" => Ok(Self::Paper),
-            "C" | "Z" => Ok(Self::Scissors),
-            _ => Err(anyhow::anyhow!("Unexpected string \"{s}\" found")),
+        let mut rules = Self {
+            signs: Vec::new(),
+            beats: Vec::new(),
+            winners: Vec::new(),
+        };
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index.saturating_add(1);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let result = match words.as_slice() {
+                ["sign", name, score, enemy_symbol, own_symbol] => score
+                    .parse()
+                    .context("Score is not a number.")
+                    .map(|score| {
+                        rules.signs.push(SignRule {
+                            name: (*name).to_owned(),
+                            score,
+                            enemy_symbol: (*enemy_symbol).to_owned(),
+                            own_symbol: (*own_symbol).to_owned(),
+                        });
+                    }),
+                ["beats", winner, losers @ ..] if !losers.is_empty() => {
+                    rules.add_beats(winner, losers)
+                }
+                ["outcome", winner, score, symbol] => winner.parse().and_then(|winner| {
+                    rules.winners.push(WinnerRule {
+                        winner,
+                        score: score.parse().context("Score is not a number.")?,
+                        symbol: (*symbol).to_owned(),
+                    });
+                    Ok(())
+                }),
+                _ => Err(anyhow::anyhow!("Unknown rule \"{line}\".")),
+            };
+            result.with_context(|| format!("in line {line_number}"))?;
         }
+        rules.validate()?;
+        Ok(rules)
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-enum RockPaperScissorsWinner {
-    Enemy,
-    Draw,
-    Myself,
-}
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl RockPaperScissorsWinner {
-    fn score(self) -> u8 {
-        match self {
-            Self::Enemy => 0,
-            Self::Draw => 3,
-            Self::Myself => 6,
-        }
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day02"],
+        test_part_1_default: "score_by_guesswork" => |input| {
+            calculate_score_by_guesswork(&GameRules::from_str(ROCK_PAPER_SCISSORS)?, input.as_bytes())
+        },
+        test_part_2_default: "score_by_elf_explanation" => |input| {
+            calculate_score_by_elf_explanation(
+                &GameRules::from_str(ROCK_PAPER_SCISSORS)?,
+                input.as_bytes(),
+            )
+        },
+    }
+
+    #[test]
+    fn test_rock_paper_scissors_lizard_spock() -> anyhow::Result<()> {
+        // Arrange
+        let rules = GameRules::from_str(include_str!(
+            "../data/day02/rock-paper-scissors-lizard-spock.rules"
+        ))?;
+        // Spock beats scissors, lizard loses to rock and Spock loses to paper.
+        let guide = "C Z\nA Y\nB Z\n";
+
+        // Act
+        let score_by_guesswork = calculate_score_by_guesswork(&rules, guide.as_bytes())?;
+        let score_by_elf_explanation =
+            calculate_score_by_elf_explanation(&rules, "B Z\n".as_bytes())?;
+
+        // Assert
+        assert_eq!(score_by_guesswork, 11 + 4 + 5);
+        assert_eq!(score_by_elf_explanation, 4 + 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_guide_analysis() -> anyhow::Result<()> {
+        // Arrange
+        let rules = GameRules::from_str(ROCK_PAPER_SCISSORS)?;
+        let tally = GuideTally::read(&rules, "A Y\nB X\nC Z\n".as_bytes())?;
+
+        // Act
+        let analysis = GuideAnalysis::analyze(&rules, &tally)?;
+
+        // Assert
+        assert_eq!(analysis.ranked.len(), 27 + 27);
+        assert!(analysis.ranked.iter().any(|ranked| ranked.interpretation
+            == Interpretation::Signs(vec![
+                ("X".to_owned(), RockPaperScissorsSign(0)),
+                ("Y".to_owned(), RockPaperScissorsSign(0)),
+                ("Z".to_owned(), RockPaperScissorsSign(0)),
+            ])));
+        assert_eq!(analysis.perfect_score, 8 + 9 + 7);
+        let part_scores = analysis
+            .ranked
+            .iter()
+            .filter_map(|ranked| Some((analysis.part_of(&ranked.interpretation)?, ranked.score)))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            part_scores,
+            BTreeMap::from([("part 1", 15), ("part 2", 12)])
+        );
+        assert!(analysis
+            .ranked
+            .windows(2)
+            .all(|pair| pair.first().map(|ranked| ranked.score)
+                >= pair.last().map(|ranked| ranked.score)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_guide_analysis_rejects_too_many_symbols() -> anyhow::Result<()> {
+        // Arrange
+        let rules = GameRules::from_str(ROCK_PAPER_SCISSORS)?;
+        let guide = ('a'..='k')
+            .map(|symbol| format!("A {symbol}\n"))
+            .collect::<String>();
+        let tally = GuideTally::read(&rules, guide.as_bytes())?;
+
+        // Act
+        let analysis = GuideAnalysis::analyze(&rules, &tally);
+
+        // Assert
+        assert!(matches!(
+            analysis,
+            Err(error) if error.to_string()
+                == "The second column has 11 symbols, too many to read them in every way."
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_opponent_model() -> anyhow::Result<()> {
+        // Arrange
+        let rules = GameRules::from_str(ROCK_PAPER_SCISSORS)?;
+        let rock = rules.enemy_sign("A")?;
+        let paper = rules.enemy_sign("B")?;
+
+        // Act
+        let model = OpponentModel::learn(&rules, "A Y\nB X\nA Z\nB Z\n".as_bytes())?;
+
+        // Assert
+        assert_eq!(model.signs, vec![2, 2, 0]);
+        assert_eq!(
+            model.transitions,
+            vec![vec![0, 2, 0], vec![1, 0, 0], vec![0; 3]]
+        );
+        assert_eq!(model.predict(Some(rock)), Some(paper));
+        assert_eq!(model.rounds, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_simulation_against_constant_opponent() -> anyhow::Result<()> {
+        // Arrange
+        let rules = GameRules::from_str(ROCK_PAPER_SCISSORS)?;
+        let model = OpponentModel::learn(&rules, "A Z\nA Z\nA Z\n".as_bytes())?;
+
+        // Act
+        let simulation = Simulation::run(&rules, &model, 10, 1)?;
+
+        // Assert
+        let counter_markov = simulation
+            .outcomes
+            .iter()
+            .find(|outcome| outcome.strategy == Strategy::CounterMarkov)
+            .ok_or_else(|| anyhow::anyhow!("counter-markov was not simulated"))?;
+        assert_eq!(counter_markov.mean, 3.0 * (2.0 + 6.0));
+        assert_eq!(counter_markov.variance, 0.0);
+        assert_eq!(
+            simulation.outcomes.last().map(|outcome| outcome.strategy),
+            Some(Strategy::Fixed(rules.enemy_sign("C")?))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rules_reject_mutual_dominance() {
+        // Arrange
+        let rules = "sign rock 1 A X\nsign paper 2 B Y\nbeats rock paper\nbeats paper rock\n\
+            outcome loss 0 X\noutcome draw 3 Y\noutcome win 6 Z\n";
+
+        // Act
+        let result = GameRules::from_str(rules);
+
+        // Assert
+        assert!(result.is_err());
     }
 }
@@ -1,4 +1,5 @@
-use std::hash::Hash;
+use std::fmt::Display;
+use std::io::BufRead;
 use std::ops::{Div, Rem};
 use std::str::FromStr;
 
@@ -6,109 +7,330 @@ use anyhow::Context;
 
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../inputs/day03.input");
+/// Items in the order of their priorities in the puzzle.
+const DEFAULT_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[derive(Debug, clap::Args)]
+struct Day03Args {
+    /// Number of equally sized compartments of every backpack.
+    #[arg(long, default_value_t = 2)]
+    compartments: usize,
+    /// Number of elves sharing a badge.
+    #[arg(long, default_value_t = 3)]
+    group_size: usize,
+    /// Items in the order of their priorities, starting at 1, e.g. `0123456789`.
+    #[arg(long, default_value = DEFAULT_ALPHABET)]
+    alphabet: Alphabet,
+    /// Lists every backpack and elf group not sharing exactly one item before solving.
+    #[arg(long)]
+    validate: bool,
+}
 
 fn main() -> anyhow::Result<()> {
-    let backpacks = INPUT
-        .lines()
-        .enumerate()
-        .map(|(index, line)| Backpack::from_str(line).context(format!("in line #{index}")))
-        .collect::<Result<Vec<_>, _>>()?;
+    let (args, day_args) = aoc_2022::cli::DayArgs::init_with::<Day03Args>()?;
+    let layout = RucksackLayout::new(
+        day_args.alphabet,
+        day_args.compartments,
+        day_args.group_size,
+    )?;
+
+    if day_args.validate {
+        let violations = validate(&layout, args.input_reader(2022, "day03")?)?;
+        for violation in &violations {
+            println!("{violation}");
+        }
+        if !violations.is_empty() {
+            return Err(anyhow::anyhow!(
+                "{} backpacks or elf groups do not share exactly one item.",
+                violations.len()
+            ));
+        }
+    }
 
     // PART 1 - 47 minutes 17 seconds
-    let sum_of_priorities = backpacks
-        .iter()
+    let sum_of_priorities = tracing::info_span!("part", number = 1)
+        .in_scope(|| calculate_sum_of_priorities(&layout, args.input_reader(2022, "day03")?))?;
+    println!("sum_of_priorities: {sum_of_priorities}");
+
+    // PART 2 - 26 minutes 25 seconds
+    let sum_of_badge_priorities = tracing::info_span!("part", number = 2).in_scope(|| {
+        calculate_sum_of_badge_priorities(&layout, args.input_reader(2022, "day03")?)
+    })?;
+    println!("sum_of_badge_priorities: {sum_of_badge_priorities}");
+
+    Ok(())
+}
+
+fn calculate_sum_of_priorities(
+    layout: &RucksackLayout,
+    reader: impl BufRead,
+) -> anyhow::Result<u32> {
+    read_backpacks(layout, reader)
         .enumerate()
         .map(|(index, backpack)| {
-            backpack
-                .find_item_type_common_in_both_compartments()
+            backpack?
+                .find_item_type_common_in_all_compartments(&layout.alphabet)
+                .and_then(|item| layout.alphabet.priority(item))
                 .context(format!("in backpack #{index}"))
         })
-        .collect::<Result<Vec<BackpackItem>, _>>()?
+        .sum()
+}
+
+fn calculate_sum_of_badge_priorities(
+    layout: &RucksackLayout,
+    reader: impl BufRead,
+) -> anyhow::Result<u32> {
+    let elf_groups = read_backpacks(layout, reader).chunks(layout.group_size);
+    elf_groups
         .into_iter()
         .enumerate()
-        .map(|(index, backpack_item)| {
-            backpack_item
-                .convert_item_into_priority()
-                .context(format!("in backpack #{index}"))
+        .map(|(index, elf_group)| {
+            let elf_group = elf_group.collect::<anyhow::Result<Vec<_>>>()?;
+            let badge = find_elf_group_badge(&elf_group, &layout.alphabet)
+                .context(format!("in elf group #{index}"))?;
+            tracing::trace!(?badge, "found elf group badge");
+            layout
+                .alphabet
+                .priority(badge)
+                .context(format!("in elf group #{index}"))
         })
-        .collect::<Result<Vec<u32>, _>>()?
-        .into_iter()
-        .sum::<u32>();
-    println!("sum_of_priorities: {sum_of_priorities}");
+        .sum()
+}
 
-    // PART 2 - 26 minutes 25 seconds
-    let sum_of_badge_priorities = backpacks
-        .chunks(3)
+/// The backpacks, read one line at a time.
+fn read_backpacks<'layout>(
+    layout: &'layout RucksackLayout,
+    reader: impl BufRead + 'layout,
+) -> impl Iterator<Item = anyhow::Result<Backpack>> + 'layout {
+    aoc_2022::stream::lines(reader)
         .enumerate()
-        .map(|(index, elf_group)| {
-            find_elf_group_badge(elf_group).context(format!("in elf group #{index}"))
-        })
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
+        .map(|(index, line)| Backpack::parse(layout, &line?).context(format!("in line #{index}")))
+}
+
+/// A backpack or elf group breaking the rule that exactly one item is shared.
+#[derive(Debug)]
+enum Violation {
+    Backpack { index: usize, error: anyhow::Error },
+    ElfGroup { index: usize, error: anyhow::Error },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Backpack { index, ref error } => write!(f, "backpack #{index}: {error:#}"),
+            Self::ElfGroup { index, ref error } => write!(f, "elf group #{index}: {error:#}"),
+        }
+    }
+}
+
+/// Every violation in the input, instead of only the first one like the parts report.
+///
+/// Elf groups with an unreadable backpack are not checked, as the backpack is reported already.
+fn validate(layout: &RucksackLayout, reader: impl BufRead) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    let elf_groups = aoc_2022::stream::lines(reader)
         .enumerate()
-        .map(|(index, backpack_item)| {
-            backpack_item
-                .convert_item_into_priority()
-                .context(format!("in backpack #{index}"))
+        .chunks(layout.group_size);
+    for (group_index, elf_group) in elf_groups.into_iter().enumerate() {
+        let mut backpacks = Vec::new();
+        let mut is_readable = true;
+        for (index, line) in elf_group {
+            match Backpack::parse(layout, &line?) {
+                Ok(backpack) => {
+                    if let Err(error) =
+                        backpack.find_item_type_common_in_all_compartments(&layout.alphabet)
+                    {
+                        violations.push(Violation::Backpack { index, error });
+                    }
+                    backpacks.push(backpack);
+                }
+                Err(error) => {
+                    is_readable = false;
+                    violations.push(Violation::Backpack { index, error });
+                }
+            }
+        }
+        if is_readable {
+            if let Err(error) = find_elf_group_badge(&backpacks, &layout.alphabet) {
+                violations.push(Violation::ElfGroup {
+                    index: group_index,
+                    error,
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// How backpacks are packed and grouped.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct RucksackLayout {
+    alphabet: Alphabet,
+    compartments: usize,
+    group_size: usize,
+}
+
+impl RucksackLayout {
+    fn new(alphabet: Alphabet, compartments: usize, group_size: usize) -> anyhow::Result<Self> {
+        if compartments == 0 {
+            return Err(anyhow::anyhow!("Backpacks need at least one compartment."));
+        }
+        if group_size == 0 {
+            return Err(anyhow::anyhow!("Elf groups need at least one elf."));
+        }
+        Ok(Self {
+            alphabet,
+            compartments,
+            group_size,
         })
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
-        .sum::<u32>();
-    println!("sum_of_badge_priorities: {sum_of_badge_priorities}");
-    Ok(())
+    }
 }
 
-struct Backpack {
-    items: Vec<BackpackItem>,
+impl Default for RucksackLayout {
+    fn default() -> Self {
+        Self {
+            alphabet: Alphabet::from_items(DEFAULT_ALPHABET.chars().collect()),
+            compartments: 2,
+            group_size: 3,
+        }
+    }
 }
 
-impl Backpack {
-    fn split_compartments(&self) -> anyhow::Result<(&[BackpackItem], &[BackpackItem])> {
-        let half_size = self.items.len().div(2);
-        Ok((
-            self.items
-                .get(..half_size)
-                .ok_or_else(|| anyhow::anyhow!("Could not get first half of backpack items."))?,
-            self.items
-                .get(half_size..)
-                .ok_or_else(|| anyhow::anyhow!("Could not get second half of backpack items."))?,
-        ))
-    }
-
-    fn find_item_type_common_in_both_compartments(&self) -> anyhow::Result<BackpackItem> {
-        let (first_compartment, second_compartment) = self.split_compartments()?;
-        let common_in_both = find_common_item_types(first_compartment, second_compartment);
-        if common_in_both.len() > 1 {
-            Err(anyhow::anyhow!(
-                "Found {} items ({:?}) common in both compartments ",
-                common_in_both.len(),
-                common_in_both
-            ))
-        } else {
-            common_in_both
-                .first()
-                .copied()
-                .copied()
-                .ok_or_else(|| anyhow::anyhow!("Found no items common in both compartments."))
+/// Items in the order of their priorities, which start at 1.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Alphabet {
+    items: Vec<char>,
+    /// Priorities of the ASCII items by their code, 0 for the ones not in the alphabet.
+    ascii_priorities: [u8; 128],
+}
+
+impl Alphabet {
+    /// Most items an [`ItemSet`] can hold.
+    const MAX_ITEMS: usize = 127;
+
+    fn from_items(items: Vec<char>) -> Self {
+        let mut ascii_priorities = [0; 128];
+        for (index, &item) in items.iter().enumerate().rev() {
+            let priority = index
+                .checked_add(1)
+                .and_then(|priority| u8::try_from(priority).ok());
+            if let (Some(slot), Some(priority)) = (
+                u8::try_from(item)
+                    .ok()
+                    .and_then(|code| ascii_priorities.get_mut(usize::from(code))),
+                priority,
+            ) {
+                *slot = priority;
+            }
         }
+        Self {
+            items,
+            ascii_priorities,
+        }
+    }
+
+    fn priority(&self, item: BackpackItem) -> anyhow::Result<u32> {
+        let ascii_priority = u8::try_from(item.0)
+            .ok()
+            .and_then(|code| self.ascii_priorities.get(usize::from(code)));
+        let priority = match ascii_priority {
+            Some(&priority) => (priority != 0).then_some(u32::from(priority)),
+            None => self
+                .items
+                .iter()
+                .position(|&known| known == item.0)
+                .and_then(|index| u32::try_from(index.checked_add(1)?).ok()),
+        };
+        priority.ok_or_else(|| anyhow::anyhow!("Item '{}' is not in the alphabet.", item.0))
+    }
+
+    fn item(&self, priority: u32) -> Option<BackpackItem> {
+        let index = usize::try_from(priority.checked_sub(1)?).ok()?;
+        self.items.get(index).copied().map(BackpackItem)
     }
 }
 
-impl FromStr for Backpack {
+impl FromStr for Alphabet {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let items = s
-            .chars()
-            .map(BackpackItem::try_from)
-            .collect::<Result<Vec<_>, _>>()?;
-        if items.len().rem(2) == 0 {
-            Ok(Self { items })
-        } else {
+        let items = s.chars().collect::<Vec<_>>();
+        if items.is_empty() {
+            return Err(anyhow::anyhow!("Alphabet is empty."));
+        }
+        if items.len() > Self::MAX_ITEMS {
+            return Err(anyhow::anyhow!(
+                "Alphabet has {} items, at most {} are supported.",
+                items.len(),
+                Self::MAX_ITEMS
+            ));
+        }
+        if let Some(item) = items
+            .iter()
+            .enumerate()
+            .find_map(|(index, item)| items.get(..index)?.contains(item).then_some(item))
+        {
+            return Err(anyhow::anyhow!(
+                "Item '{item}' appears more than once in the alphabet."
+            ));
+        }
+        Ok(Self::from_items(items))
+    }
+}
+
+/// A backpack as the items of all compartments and the items common in all of them.
+struct Backpack {
+    items: ItemSet,
+    common: ItemSet,
+}
+
+impl Backpack {
+    fn parse(layout: &RucksackLayout, line: &str) -> anyhow::Result<Self> {
+        let item_count = line.chars().count();
+        if item_count.rem(layout.compartments) != 0 {
+            return Err(anyhow::anyhow!(
+                "Items in the backpack are not evenly distributed over {} compartments.",
+                layout.compartments
+            ));
+        }
+        let compartment_size = item_count.div(layout.compartments);
+        let mut items = ItemSet::default();
+        let mut common: Option<ItemSet> = None;
+        let mut compartment = ItemSet::default();
+        for (index, item) in line.chars().enumerate() {
+            compartment.insert(layout.alphabet.priority(BackpackItem(item))?)?;
+            if index
+                .checked_add(1)
+                .and_then(|count| count.checked_rem(compartment_size))
+                == Some(0)
+            {
+                items = items.union(compartment);
+                common =
+                    Some(common.map_or(compartment, |common| common.intersection(compartment)));
+                compartment = ItemSet::default();
+            }
+        }
+        Ok(Self {
+            items,
+            common: common.unwrap_or_default(),
+        })
+    }
+
+    fn find_item_type_common_in_all_compartments(
+        &self,
+        alphabet: &Alphabet,
+    ) -> anyhow::Result<BackpackItem> {
+        if self.common.len() > 1 {
             Err(anyhow::anyhow!(
-                "Items in the backpack are not evenly distributed."
+                "Found {} items ({:?}) common in all compartments.",
+                self.common.len(),
+                self.common.items(alphabet).collect::<Vec<_>>()
             ))
+        } else {
+            self.common
+                .only_priority()
+                .and_then(|priority| alphabet.item(priority))
+                .ok_or_else(|| anyhow::anyhow!("Found no items common in all compartments."))
         }
     }
 }
@@ -116,74 +338,173 @@ impl FromStr for Backpack {
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 struct BackpackItem(char);
 
-impl TryFrom<char> for BackpackItem {
-    type Error = anyhow::Error;
-
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            'a'..='z' | 'A'..='Z' => Ok(Self(value)),
-            _ => Err(anyhow::anyhow!(
-                "Item '{}' is not within specified range of a..z or A..Z.",
-                value
-            )),
-        }
-    }
-}
-
-impl BackpackItem {
-    fn convert_item_into_priority(self) -> Result<u32, anyhow::Error> {
-        let offset = match self.0 {
-            'a'..='z' => u32::from('a')
-                .checked_sub(1)
-                .ok_or_else(|| anyhow::anyhow!("Could not subtract 1 from u32::from('a').")),
-            'A'..='Z' => u32::from('A')
-                .checked_sub(27)
-                .ok_or_else(|| anyhow::anyhow!("Could not subtract 27 from u32::from('A').")),
-            value => Err(anyhow::anyhow!(
-                "Item '{}' is not within specified range of a..z or A..Z.",
-                value
-            )),
-        }?;
-        u32::from(self.0).checked_sub(offset).ok_or_else(|| {
-            anyhow::anyhow!("Could not convert backpack item '{}' into priority", self.0)
-        })
+/// Item priorities as one bit each, so sets combine in a single instruction.
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Hash)]
+struct ItemSet(u128);
+
+impl ItemSet {
+    fn insert(&mut self, priority: u32) -> anyhow::Result<()> {
+        self.0 |= 1_u128
+            .checked_shl(priority)
+            .ok_or_else(|| anyhow::anyhow!("Priority {priority} does not fit into an item set."))?;
+        Ok(())
     }
-}
 
-fn find_common_item_types<'item, T>(first: &'item [T], second: &'item [T]) -> Vec<&'item T>
-where
-    T: Eq + PartialEq + Hash,
-{
-    first
-        .iter()
-        .filter_map(|first_item| {
-            second
-                .iter()
-                .any(|second_item| second_item == first_item)
-                .then_some(first_item)
-        })
-        .unique()
-        .collect::<Vec<_>>()
+    fn contains(self, priority: u32) -> bool {
+        1_u128
+            .checked_shl(priority)
+            .is_some_and(|bit| self.0 & bit != 0)
+    }
+
+    fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// The priority, if the set holds exactly one.
+    fn only_priority(self) -> Option<u32> {
+        (self.len() == 1).then(|| self.0.trailing_zeros())
+    }
+
+    fn items(self, alphabet: &Alphabet) -> impl Iterator<Item = BackpackItem> + '_ {
+        (0..u128::BITS)
+            .filter(move |&priority| self.contains(priority))
+            .filter_map(|priority| alphabet.item(priority))
+    }
 }
 
-fn find_elf_group_badge(elf_group: &[Backpack]) -> anyhow::Result<BackpackItem> {
-    let containing_elements = elf_group.iter().fold(None, |store, backpack| match store {
-        None => Some(backpack.items.clone()),
-        Some(mut s) => {
-            s.retain(|backpack_item| backpack.items.contains(backpack_item));
-            Some(s)
-        }
-    });
+fn find_elf_group_badge(
+    elf_group: &[Backpack],
+    alphabet: &Alphabet,
+) -> anyhow::Result<BackpackItem> {
+    let containing_elements = elf_group
+        .iter()
+        .map(|backpack| backpack.items)
+        .reduce(ItemSet::intersection);
     match containing_elements {
         None => Err(anyhow::anyhow!("Elf group is empty.")),
         Some(items) if items.len() > 1 => Err(anyhow::anyhow!(
             "Elf group has {} shared items ({:?}).",
             items.len(),
-            items
+            items.items(alphabet).collect::<Vec<_>>()
         )),
         Some(items) => items
-            .first()
-            .copied()
+            .only_priority()
+            .and_then(|priority| alphabet.item(priority))
             .ok_or_else(|| anyhow::anyhow!("Elf group has no shared item.")),
     }
 }
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day03"],
+        test_part_1_default: "sum_of_priorities" => |input| {
+            calculate_sum_of_priorities(&RucksackLayout::default(), input.as_bytes())
+        },
+        test_part_2_default: "sum_of_badge_priorities" => |input| {
+            calculate_sum_of_badge_priorities(&RucksackLayout::default(), input.as_bytes())
+        },
+    }
+
+    #[test]
+    fn test_find_elf_group_badge_with_repeated_badge() -> anyhow::Result<()> {
+        // Arrange
+        let layout = RucksackLayout::default();
+        let elf_group = ["DaDb", "cDdD", "DefD"]
+            .into_iter()
+            .map(|line| Backpack::parse(&layout, line))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Act
+        let badge = find_elf_group_badge(&elf_group, &layout.alphabet)?;
+
+        // Assert
+        assert_eq!(badge, BackpackItem('D'));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_set() -> anyhow::Result<()> {
+        // Arrange
+        let alphabet = RucksackLayout::default().alphabet;
+        let mut first = ItemSet::default();
+        let mut second = ItemSet::default();
+        for item in ['a', 'Z', 'a'] {
+            first.insert(alphabet.priority(BackpackItem(item))?)?;
+        }
+        for item in ['Z', 'q'] {
+            second.insert(alphabet.priority(BackpackItem(item))?)?;
+        }
+
+        // Act
+        let intersection = first.intersection(second);
+        let union = first.union(second);
+
+        // Assert
+        assert_eq!(intersection.only_priority(), Some(52));
+        assert_eq!(union.len(), 3);
+        assert!(union.contains(17));
+        assert!(!intersection.contains(1));
+        assert_eq!(
+            union.items(&alphabet).collect::<Vec<_>>(),
+            vec![BackpackItem('a'), BackpackItem('q'), BackpackItem('Z')]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_layout() -> anyhow::Result<()> {
+        // Arrange
+        let layout = RucksackLayout::new(Alphabet::from_str("0123456789äöü")?, 3, 2)?;
+        let input = "1ä21ü31ö4\nü0üü9üü8ü\n";
+
+        // Act
+        let sum_of_priorities = calculate_sum_of_priorities(&layout, input.as_bytes())?;
+        let sum_of_badge_priorities = calculate_sum_of_badge_priorities(&layout, input.as_bytes())?;
+
+        // Assert
+        assert_eq!(sum_of_priorities, 2 + 13);
+        assert_eq!(sum_of_badge_priorities, 13);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_reports_every_violation() -> anyhow::Result<()> {
+        // Arrange
+        let layout = RucksackLayout::default();
+        let input = "abca\nabcd\nbacb\nxyz\nqq\nqq\n";
+
+        // Act
+        let violations = validate(&layout, input.as_bytes())?
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        // Assert
+        assert_eq!(
+            violations,
+            vec![
+                "backpack #1: Found no items common in all compartments.",
+                "elf group #0: Elf group has 3 shared items ([BackpackItem('a'), BackpackItem('b'), BackpackItem('c')]).",
+                "backpack #3: Items in the backpack are not evenly distributed over 2 compartments.",
+            ]
+        );
+
+        Ok(())
+    }
+}
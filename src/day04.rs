@@ -1,47 +1,158 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::BufRead;
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::Context;
 
-const INPUT: &str = include_str!("../inputs/day04.input");
+#[derive(Debug, clap::Args)]
+struct Day04Args {
+    /// Prints which sections are unassigned, covered by the most elves and cleaned twice by pairs.
+    #[arg(long)]
+    coverage: bool,
+    /// Prints the elves assigned to a section or any of a range of sections, e.g. `42` or `10-20`.
+    #[arg(long)]
+    who: Vec<SectionQuery<u8>>,
+    /// Writes the input with the assignments of overlapping pairs split apart to this file and
+    /// prints the changes.
+    #[arg(long)]
+    plan: Option<PathBuf>,
+}
 
 fn main() -> anyhow::Result<()> {
+    let (args, day_args) = aoc_2022::cli::DayArgs::init_with::<Day04Args>()?;
+
     // PART 1 - 24 minutes 45 seconds
-    let part_1_solution = calculate_count_of_fully_containing_pairs(INPUT)?;
+    let part_1_solution = tracing::info_span!("part", number = 1).in_scope(|| {
+        calculate_count_of_fully_containing_pairs(args.input_reader(2022, "day04")?)
+    })?;
     println!("part_1_solution: {part_1_solution}");
 
     // PART 2 - 6 minutes 45 seconds
-    let part_2_solution = calculate_count_of_overlapping_at_all_pairs(INPUT)?;
+    let part_2_solution = tracing::info_span!("part", number = 2).in_scope(|| {
+        calculate_count_of_overlapping_at_all_pairs(args.input_reader(2022, "day04")?)
+    })?;
     println!("part_2_solution: {part_2_solution}");
 
+    if let Some(ref path) = day_args.plan {
+        let index = SectionIndex::<u8>::read(args.input_reader(2022, "day04")?)?;
+        let plan = ReassignmentPlan::new(&index.pairs)?;
+        print!(
+            "{}",
+            PlanDiff {
+                plan: &plan,
+                from: &args.input_path(2022, "day04"),
+                to: path,
+            }
+        );
+        let corrected = plan.corrected_input();
+        std::fs::write(path, &corrected)
+            .with_context(|| format!("while writing corrected input {}", path.display()))?;
+        println!(
+            "overlapping pairs in {}: {}",
+            path.display(),
+            calculate_count_of_overlapping_at_all_pairs(corrected.as_bytes())?
+        );
+    }
+
+    if day_args.coverage || !day_args.who.is_empty() || args.render_dir.is_some() {
+        let index = SectionIndex::<u8>::read(args.input_reader(2022, "day04")?)?;
+        for &query in &day_args.who {
+            let elves = match query {
+                SectionQuery::Section(id) => index.covering(id).collect::<Vec<_>>(),
+                SectionQuery::Range(sections) => index.overlapping(sections).collect(),
+            };
+            let elves = elves
+                .into_iter()
+                .map(|(assignment, elf)| format!("{elf} ({assignment})"))
+                .collect::<Vec<_>>();
+            println!("{query}: {} elves", elves.len());
+            for elf in elves {
+                println!("  {elf}");
+            }
+        }
+        let coverage = CoverageAnalysis::new(&index);
+        if day_args.coverage {
+            print!("{coverage}");
+        }
+        args.render("coverage", || coverage.to_string())?;
+    }
+
     Ok(())
 }
 
-fn calculate_count_of_fully_containing_pairs(input: &str) -> anyhow::Result<usize> {
-    Ok(parse_elf_pairs(input)?
-        .into_iter()
-        .filter(|elf_pair| elf_pair.one_fully_contains_the_other())
-        .count())
+fn calculate_count_of_fully_containing_pairs(reader: impl BufRead) -> anyhow::Result<usize> {
+    count_elf_pairs(reader, ElfPair::one_fully_contains_the_other)
 }
 
-fn calculate_count_of_overlapping_at_all_pairs(input: &str) -> anyhow::Result<usize> {
-    Ok(parse_elf_pairs(input)?
-        .into_iter()
-        .filter(|elf_pair| elf_pair.are_overlapping_at_all())
-        .count())
+fn calculate_count_of_overlapping_at_all_pairs(reader: impl BufRead) -> anyhow::Result<usize> {
+    count_elf_pairs(reader, ElfPair::are_overlapping_at_all)
 }
 
-fn parse_elf_pairs(input: &str) -> anyhow::Result<Vec<ElfPair>> {
-    input
-        .lines()
+/// Counts the elf pairs matching the predicate while reading them line by line.
+fn count_elf_pairs(
+    reader: impl BufRead,
+    predicate: impl Fn(ElfPair<u8>) -> bool,
+) -> anyhow::Result<usize> {
+    aoc_2022::stream::lines(reader)
         .enumerate()
-        .map(|(index, line)| ElfPair::from_str(line).context(format!("in line #{index}")))
-        .collect::<Result<Vec<_>, _>>()
+        .try_fold(0_usize, |count, (index, line)| {
+            let elf_pair = ElfPair::<u8>::from_str(&line?).context(format!("in line #{index}"))?;
+            Ok(if predicate(elf_pair) {
+                count.saturating_add(1)
+            } else {
+                count
+            })
+        })
 }
 
+/// Unsigned integers section ids are stored in.
+trait SectionNumber: Copy + Ord + std::fmt::Debug + Display + FromStr<Err = ParseIntError> {
+    const MAX: Self;
+
+    fn checked_next(self) -> Option<Self>;
+
+    fn checked_previous(self) -> Option<Self>;
+
+    fn widen(self) -> u64;
+
+    fn narrow(value: u64) -> Option<Self>;
+}
+
+macro_rules! impl_section_number {
+    ($($number:ty),*) => {
+        $(
+            impl SectionNumber for $number {
+                const MAX: Self = <$number>::MAX;
+
+                fn checked_next(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn checked_previous(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn widen(self) -> u64 {
+                    u64::from(self)
+                }
+
+                fn narrow(value: u64) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_section_number!(u8, u16, u32, u64);
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-struct ElfPair(SectionAssignment, SectionAssignment);
+struct ElfPair<N>(SectionAssignment<N>, SectionAssignment<N>);
 
-impl ElfPair {
+impl<N: SectionNumber> ElfPair<N> {
     fn one_fully_contains_the_other(self) -> bool {
         self.0.fully_contains(self.1) || self.1.fully_contains(self.0)
     }
@@ -49,13 +160,83 @@ impl ElfPair {
     fn are_overlapping_at_all(self) -> bool {
         self.0.overlaps(self.1)
     }
+
+    /// The pair with the sections of both elves split between them, changing as few sections
+    /// as possible.
+    ///
+    /// Both elves of a pair on the same single section cannot be split, so the second one moves
+    /// to a neighbouring section that is assigned to some elf already.
+    fn separate(self, assigned: &SectionSet<N>) -> anyhow::Result<Self> {
+        let Some(union) = self
+            .0
+            .union(self.1)
+            .filter(|_| self.are_overlapping_at_all())
+        else {
+            return Ok(self);
+        };
+        if union.from == union.to {
+            let neighbour = [union.to.0.checked_next(), union.from.0.checked_previous()]
+                .into_iter()
+                .flatten()
+                .find(|&id| assigned.contains(SectionId(id)))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Pair {self} has no assigned section to move an elf to.")
+                })?;
+            return Ok(Self(self.0, SectionAssignment::new(neighbour, neighbour)));
+        }
+        let split = |left: SectionAssignment<N>, right: SectionAssignment<N>| {
+            let (from, to) = (union.from.0.widen(), union.to.0.widen());
+            let last_cut = to.checked_sub(1)?;
+            // Sections changed are |left.to - cut| + |right.from - 1 - cut| plus a constant, so
+            // any cut between both ends is best; the middle splits the shared sections evenly.
+            let left_end = left.to.0.widen();
+            let right_start = right.from.0.widen().saturating_sub(1);
+            let low = left_end.min(right_start).clamp(from, last_cut);
+            let high = left_end.max(right_start).clamp(from, last_cut);
+            let cut = low.checked_add(high.checked_sub(low)?.checked_div(2)?)?;
+            Some((
+                SectionAssignment::new(N::narrow(from)?, N::narrow(cut)?),
+                SectionAssignment::new(N::narrow(cut.checked_add(1)?)?, N::narrow(to)?),
+            ))
+        };
+        [
+            split(self.0, self.1).map(|(first, second)| Self(first, second)),
+            split(self.1, self.0).map(|(second, first)| Self(first, second)),
+        ]
+        .into_iter()
+        .flatten()
+        .min_by_key(|separated| self.changed_sections(*separated))
+        .ok_or_else(|| anyhow::anyhow!("Could not split pair {self}."))
+    }
+
+    /// Sections either elf gains or loses when reassigned to the other pair.
+    fn changed_sections(self, other: Self) -> u64 {
+        [(self.0, other.0), (self.1, other.1)]
+            .into_iter()
+            .flat_map(|(before, after)| {
+                before
+                    .difference(after)
+                    .into_iter()
+                    .chain(after.difference(before))
+            })
+            .flatten()
+            .fold(0, |changed, assignment| {
+                changed.saturating_add(assignment.len())
+            })
+    }
 }
 
-impl FromStr for ElfPair {
+impl<N: SectionNumber> Display for ElfPair<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.0, self.1)
+    }
+}
+
+impl<N: SectionNumber> FromStr for ElfPair<N> {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pair: [SectionAssignment; 2] = s
+        let pair: [SectionAssignment<N>; 2] = s
             .split(',')
             .map(SectionAssignment::from_str)
             .collect::<Result<Vec<_>, _>>()?
@@ -71,13 +252,22 @@ impl FromStr for ElfPair {
     }
 }
 
+/// The sections from one id to another, both included.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-struct SectionAssignment {
-    from: SectionId,
-    to: SectionId,
+struct SectionAssignment<N> {
+    from: SectionId<N>,
+    to: SectionId<N>,
 }
 
-impl SectionAssignment {
+impl<N: SectionNumber> SectionAssignment<N> {
+    /// The sections between both ids, in whichever order they are given.
+    fn new(from: N, to: N) -> Self {
+        Self {
+            from: SectionId(from.min(to)),
+            to: SectionId(from.max(to)),
+        }
+    }
+
     fn fully_contains(self, other: Self) -> bool {
         self.from <= other.from && self.to >= other.to
     }
@@ -85,13 +275,74 @@ impl SectionAssignment {
     fn overlaps(self, other: Self) -> bool {
         self.from <= other.to && self.to >= other.from
     }
+
+    /// Whether the other assignment starts right after this one ends, or the other way round.
+    fn touches(self, other: Self) -> bool {
+        self.to.0.checked_next() == Some(other.from.0)
+            || other.to.0.checked_next() == Some(self.from.0)
+    }
+
+    fn len(self) -> u64 {
+        self.to
+            .0
+            .widen()
+            .saturating_sub(self.from.0.widen())
+            .saturating_add(1)
+    }
+
+    fn intersection(self, other: Self) -> Option<Self> {
+        self.overlaps(other).then(|| Self {
+            from: self.from.max(other.from),
+            to: self.to.min(other.to),
+        })
+    }
+
+    /// The assignment covering both, if they overlap or touch, as there is a gap otherwise.
+    fn union(self, other: Self) -> Option<Self> {
+        (self.overlaps(other) || self.touches(other)).then(|| Self {
+            from: self.from.min(other.from),
+            to: self.to.max(other.to),
+        })
+    }
+
+    /// The sections not in the other assignment, before and after it.
+    fn difference(self, other: Self) -> [Option<Self>; 2] {
+        if !self.overlaps(other) {
+            return [Some(self), None];
+        }
+        let before = other
+            .from
+            .0
+            .checked_previous()
+            .filter(|_| self.from < other.from)
+            .map(|to| Self {
+                from: self.from,
+                to: SectionId(to),
+            });
+        let after = other
+            .to
+            .0
+            .checked_next()
+            .filter(|_| self.to > other.to)
+            .map(|from| Self {
+                from: SectionId(from),
+                to: self.to,
+            });
+        [before, after]
+    }
 }
 
-impl FromStr for SectionAssignment {
+impl<N: SectionNumber> Display for SectionAssignment<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.from.0, self.to.0)
+    }
+}
+
+impl<N: SectionNumber> FromStr for SectionAssignment<N> {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pair: [SectionId; 2] = s
+        let pair: [SectionId<N>; 2] = s
             .split('-')
             .map(SectionId::from_str)
             .collect::<Result<Vec<_>, _>>()?
@@ -99,21 +350,438 @@ impl FromStr for SectionAssignment {
             .map_err(|vec: Vec<_>| {
                 anyhow::anyhow!("Did not get 2 section ids but {} ({:?})", vec.len(), vec)
             })?;
-        Ok(Self {
-            from: pair[0].min(pair[1]),
-            to: pair[0].max(pair[1]),
-        })
+        let [from, to] = pair;
+        if from > to {
+            return Err(anyhow::anyhow!(
+                "Section assignment {s} ends before it starts."
+            ));
+        }
+        Ok(Self { from, to })
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+struct SectionId<N>(N);
+
+impl<N: SectionNumber> FromStr for SectionId<N> {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse::<N>()?))
+    }
+}
+
+/// Disjoint assignments ordered by their sections, merged from any number of assignments.
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct SectionSet<N> {
+    assignments: Vec<SectionAssignment<N>>,
+}
+
+impl<N> Default for SectionSet<N> {
+    fn default() -> Self {
+        Self {
+            assignments: Vec::new(),
+        }
+    }
+}
+
+impl<N: SectionNumber> SectionSet<N> {
+    fn insert(&mut self, assignment: SectionAssignment<N>) {
+        let mut merged = assignment;
+        self.assignments
+            .retain(|&existing| match merged.union(existing) {
+                Some(union) => {
+                    merged = union;
+                    false
+                }
+                None => true,
+            });
+        let index = self
+            .assignments
+            .partition_point(|existing| existing.from < merged.from);
+        self.assignments.insert(index, merged);
+    }
+
+    fn contains(&self, id: SectionId<N>) -> bool {
+        let index = self
+            .assignments
+            .partition_point(|assignment| assignment.to < id);
+        self.assignments
+            .get(index)
+            .is_some_and(|assignment| assignment.from <= id)
     }
+
+    fn len(&self) -> u64 {
+        self.assignments
+            .iter()
+            .fold(0, |len, assignment| len.saturating_add(assignment.len()))
+    }
+
+    /// The sections in this set but not in the other one.
+    fn difference(&self, other: &Self) -> Self {
+        let assignments = self
+            .assignments
+            .iter()
+            .flat_map(|&assignment| {
+                other
+                    .assignments
+                    .iter()
+                    .fold(vec![assignment], |remaining, &removed| {
+                        remaining
+                            .into_iter()
+                            .flat_map(|part| part.difference(removed).into_iter().flatten())
+                            .collect()
+                    })
+            })
+            .collect();
+        Self { assignments }
+    }
+
+    /// The sections between the first and the last one that are not in the set.
+    fn gaps(&self) -> Self {
+        match (self.assignments.first(), self.assignments.last()) {
+            (Some(first), Some(last)) => Self {
+                assignments: vec![SectionAssignment {
+                    from: first.from,
+                    to: last.to,
+                }],
+            }
+            .difference(self),
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<N: SectionNumber> Display for SectionSet<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.assignments.is_empty() {
+            return f.write_str("none");
+        }
+        for (index, assignment) in self.assignments.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{assignment}")?;
+        }
+        Ok(())
+    }
+}
+
+/// One side of an elf pair.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+enum Side {
+    First,
+    Second,
 }
 
+/// An elf, by the index of its pair in the input and its side in the pair.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-struct SectionId(u8);
+struct Elf {
+    pair: usize,
+    side: Side,
+}
+
+impl Display for Elf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let side = match self.side {
+            Side::First => "first",
+            Side::Second => "second",
+        };
+        write!(f, "pair #{} {side}", self.pair)
+    }
+}
 
-impl FromStr for SectionId {
+/// Sections to find the assigned elves for.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum SectionQuery<N> {
+    Section(SectionId<N>),
+    Range(SectionAssignment<N>),
+}
+
+impl<N: SectionNumber> FromStr for SectionQuery<N> {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.parse::<u8>()?))
+        if s.contains('-') {
+            Ok(Self::Range(s.parse()?))
+        } else {
+            Ok(Self::Section(s.parse()?))
+        }
+    }
+}
+
+impl<N: SectionNumber> Display for SectionQuery<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Section(id) => write!(f, "section {}", id.0),
+            Self::Range(sections) => write!(f, "sections {sections}"),
+        }
+    }
+}
+
+/// All elf pairs, with their assignments sorted to find the elves on some sections quickly.
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct SectionIndex<N> {
+    pairs: Vec<ElfPair<N>>,
+    /// Assignments of all elves, ordered by their first section.
+    elves: Vec<(SectionAssignment<N>, Elf)>,
+    /// `reach[i]` is the last section of any of `elves[..=i]`.
+    reach: Vec<SectionId<N>>,
+}
+
+impl<N: SectionNumber> SectionIndex<N> {
+    fn read(reader: impl BufRead) -> anyhow::Result<Self> {
+        let pairs = aoc_2022::stream::lines(reader)
+            .enumerate()
+            .map(|(index, line)| {
+                ElfPair::<N>::from_str(&line?).context(format!("in line #{index}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self::new(pairs))
+    }
+
+    fn new(pairs: Vec<ElfPair<N>>) -> Self {
+        let mut elves = pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(pair, elf_pair)| {
+                [
+                    (
+                        elf_pair.0,
+                        Elf {
+                            pair,
+                            side: Side::First,
+                        },
+                    ),
+                    (
+                        elf_pair.1,
+                        Elf {
+                            pair,
+                            side: Side::Second,
+                        },
+                    ),
+                ]
+            })
+            .collect::<Vec<_>>();
+        elves.sort_unstable();
+        let reach = elves
+            .iter()
+            .scan(
+                None,
+                |reach: &mut Option<SectionId<N>>, &(assignment, _)| {
+                    let next = reach.map_or(assignment.to, |reach| reach.max(assignment.to));
+                    *reach = Some(next);
+                    Some(next)
+                },
+            )
+            .collect();
+        Self {
+            pairs,
+            elves,
+            reach,
+        }
+    }
+
+    /// The elves assigned to any of the sections, ordered by their assignments.
+    ///
+    /// Only the elves starting before the sections end and not ending before them according to
+    /// `reach` are looked at.
+    fn overlapping(
+        &self,
+        sections: SectionAssignment<N>,
+    ) -> impl Iterator<Item = (SectionAssignment<N>, Elf)> + '_ {
+        let end = self
+            .elves
+            .partition_point(|&(assignment, _)| assignment.from <= sections.to);
+        let start = self.reach.partition_point(|&reach| reach < sections.from);
+        self.elves
+            .get(start..end)
+            .unwrap_or_default()
+            .iter()
+            .copied()
+            .filter(move |&(assignment, _)| assignment.overlaps(sections))
+    }
+
+    /// The elves assigned to the section.
+    fn covering(&self, id: SectionId<N>) -> impl Iterator<Item = (SectionAssignment<N>, Elf)> + '_ {
+        self.overlapping(SectionAssignment { from: id, to: id })
+    }
+
+    /// The most elves assigned to one section and the sections with that many, by a sweep over
+    /// the first and last sections of all assignments.
+    fn max_simultaneous(&self) -> (usize, SectionSet<N>) {
+        // Elves starting and stopping to be assigned at each section.
+        let mut changes: BTreeMap<N, (usize, usize)> = BTreeMap::new();
+        for &(assignment, _) in &self.elves {
+            let starts = &mut changes.entry(assignment.from.0).or_default().0;
+            *starts = starts.saturating_add(1);
+            if let Some(after) = assignment.to.0.checked_next() {
+                let stops = &mut changes.entry(after).or_default().1;
+                *stops = stops.saturating_add(1);
+            }
+        }
+        let mut coverage = 0_usize;
+        let mut segments = Vec::new();
+        let mut changes = changes.into_iter().peekable();
+        while let Some((from, (starts, stops))) = changes.next() {
+            coverage = coverage.saturating_add(starts).saturating_sub(stops);
+            let to = match changes.peek() {
+                Some(&(next, _)) => next.checked_previous(),
+                None => (coverage > 0).then_some(N::MAX),
+            };
+            if let Some(to) = to {
+                segments.push((coverage, SectionAssignment::new(from, to)));
+            }
+        }
+        let max_coverage = segments
+            .iter()
+            .map(|&(coverage, _)| coverage)
+            .max()
+            .unwrap_or(0);
+        let mut most_covered = SectionSet::default();
+        if max_coverage > 0 {
+            for (_, segment) in segments
+                .into_iter()
+                .filter(|&(coverage, _)| coverage == max_coverage)
+            {
+                most_covered.insert(segment);
+            }
+        }
+        (max_coverage, most_covered)
+    }
+}
+
+/// How the sections are covered by the assignments of all elves.
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct CoverageAnalysis<N> {
+    pairs: usize,
+    assigned: SectionSet<N>,
+    /// Most elves assigned to one section.
+    max_coverage: usize,
+    most_covered: SectionSet<N>,
+    /// Sections cleaned by both elves of a pair, summed over all pairs.
+    redundant_sections: u64,
+}
+
+impl<N: SectionNumber> CoverageAnalysis<N> {
+    fn new(index: &SectionIndex<N>) -> Self {
+        let mut assigned = SectionSet::default();
+        for &(assignment, _) in &index.elves {
+            assigned.insert(assignment);
+        }
+        let redundant_sections = index.pairs.iter().fold(0_u64, |redundant, elf_pair| {
+            redundant.saturating_add(
+                elf_pair
+                    .0
+                    .intersection(elf_pair.1)
+                    .map_or(0, SectionAssignment::len),
+            )
+        });
+        let (max_coverage, most_covered) = index.max_simultaneous();
+        Self {
+            pairs: index.pairs.len(),
+            assigned,
+            max_coverage,
+            most_covered,
+            redundant_sections,
+        }
+    }
+}
+
+impl<N: SectionNumber> Display for CoverageAnalysis<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "elf pairs: {}", self.pairs)?;
+        writeln!(
+            f,
+            "assigned sections: {} ({} sections)",
+            self.assigned,
+            self.assigned.len()
+        )?;
+        writeln!(f, "unassigned sections: {}", self.assigned.gaps())?;
+        writeln!(
+            f,
+            "most covered sections: {} ({} elves)",
+            self.most_covered, self.max_coverage
+        )?;
+        writeln!(
+            f,
+            "sections cleaned twice within pairs: {}",
+            self.redundant_sections
+        )
+    }
+}
+
+/// The elf pairs with overlapping assignments split apart, keeping the sections of every pair.
+#[derive(Clone, Eq, PartialEq, Debug)]
+struct ReassignmentPlan<N> {
+    before: Vec<ElfPair<N>>,
+    after: Vec<ElfPair<N>>,
+}
+
+impl<N: SectionNumber> ReassignmentPlan<N> {
+    fn new(pairs: &[ElfPair<N>]) -> anyhow::Result<Self> {
+        let mut assigned = SectionSet::default();
+        for elf_pair in pairs {
+            assigned.insert(elf_pair.0);
+            assigned.insert(elf_pair.1);
+        }
+        let after = pairs
+            .iter()
+            .enumerate()
+            .map(|(index, elf_pair)| {
+                elf_pair
+                    .separate(&assigned)
+                    .context(format!("in line #{index}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            before: pairs.to_vec(),
+            after,
+        })
+    }
+
+    fn changes(&self) -> impl Iterator<Item = (usize, ElfPair<N>, ElfPair<N>)> + '_ {
+        self.before
+            .iter()
+            .zip(&self.after)
+            .enumerate()
+            .filter(|&(_, (before, after))| before != after)
+            .map(|(index, (&before, &after))| (index, before, after))
+    }
+
+    fn corrected_input(&self) -> String {
+        self.after
+            .iter()
+            .map(|elf_pair| format!("{elf_pair}\n"))
+            .collect()
+    }
+}
+
+/// The changes of a plan as a unified diff from the input to the corrected input.
+struct PlanDiff<'plan, N> {
+    plan: &'plan ReassignmentPlan<N>,
+    from: &'plan Path,
+    to: &'plan Path,
+}
+
+/// The file headers and one hunk per changed line, followed by a summary.
+impl<N: SectionNumber> Display for PlanDiff<'_, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "--- {}", self.from.display())?;
+        writeln!(f, "+++ {}", self.to.display())?;
+        let mut changed_pairs = 0_usize;
+        let mut changed_sections = 0_u64;
+        for (index, before, after) in self.plan.changes() {
+            let line = index.saturating_add(1);
+            writeln!(f, "@@ -{line} +{line} @@\n-{before}\n+{after}")?;
+            changed_pairs = changed_pairs.saturating_add(1);
+            changed_sections = changed_sections.saturating_add(before.changed_sections(after));
+        }
+        writeln!(
+            f,
+            "{changed_pairs} of {} pairs reassigned, {changed_sections} sections changed",
+            self.plan.before.len()
+        )
     }
 }
 
@@ -122,31 +790,200 @@ impl FromStr for SectionId {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day04"],
+        test_part_1_default: "part_1_solution" => |input| {
+            calculate_count_of_fully_containing_pairs(input.as_bytes())
+        },
+        test_part_2_default: "part_2_solution" => |input| {
+            calculate_count_of_overlapping_at_all_pairs(input.as_bytes())
+        },
+    }
+
+    const SAMPLE: &str = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n";
 
     #[test]
-    fn default_test_part_1() -> anyhow::Result<()> {
+    fn test_interval_algebra() -> anyhow::Result<()> {
+        // Arrange
+        let outer = SectionAssignment::<u32>::from_str("100000-300000")?;
+        let inner = SectionAssignment::<u32>::from_str("200000-200009")?;
+        let after = SectionAssignment::<u32>::from_str("300001-300005")?;
+
+        // Act
+        let intersection = outer.intersection(inner);
+        let union = outer.union(after);
+        let difference = outer.difference(inner);
+
+        // Assert
+        assert_eq!(intersection, Some(inner));
+        assert_eq!(union, Some(SectionAssignment::new(100_000, 300_005)));
+        assert_eq!(inner.union(after), None);
+        assert_eq!(
+            difference,
+            [
+                Some(SectionAssignment::new(100_000, 199_999)),
+                Some(SectionAssignment::new(200_010, 300_000))
+            ]
+        );
+        assert_eq!(inner.difference(outer), [None, None]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reversed_assignment_is_rejected() {
+        // Act
+        let error = ElfPair::<u8>::from_str("5-3,1-2");
+
+        // Assert
+        assert!(matches!(
+            error,
+            Err(error) if error.to_string() == "Section assignment 5-3 ends before it starts."
+        ));
+    }
+
+    #[test]
+    fn test_section_set_merges_assignments() {
+        // Arrange
+        let mut set = SectionSet::default();
+
+        // Act
+        for (from, to) in [(10_u8, 12), (1, 2), (5, 6), (3, 3), (14, 20)] {
+            set.insert(SectionAssignment::new(from, to));
+        }
+
+        // Assert
+        assert_eq!(set.to_string(), "1-3, 5-6, 10-12, 14-20");
+        assert_eq!(set.gaps().to_string(), "4-4, 7-9, 13-13");
+        assert_eq!(set.len(), 3 + 2 + 3 + 7);
+    }
+
+    #[test]
+    fn test_section_index_queries() -> anyhow::Result<()> {
+        // Arrange
+        let index = SectionIndex::<u8>::read(SAMPLE.as_bytes())?;
+        let elves = |found: Vec<(SectionAssignment<u8>, Elf)>| {
+            let mut elves = found
+                .into_iter()
+                .map(|(_, elf)| elf.to_string())
+                .collect::<Vec<_>>();
+            elves.sort_unstable();
+            elves
+        };
+
+        // Act
+        let on_nine = elves(index.covering(SectionId(9)).collect());
+        let on_eight_to_nine = elves(index.overlapping(SectionAssignment::new(8, 9)).collect());
+        let on_one = elves(index.covering(SectionId(1)).collect());
+
+        // Assert
+        assert_eq!(on_nine, vec!["pair #2 second"]);
+        assert_eq!(
+            on_eight_to_nine,
+            vec![
+                "pair #0 second",
+                "pair #2 second",
+                "pair #3 first",
+                "pair #5 second"
+            ]
+        );
+        assert!(on_one.is_empty());
+        assert_eq!(index.max_simultaneous().0, 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reassignment_plan_removes_overlaps() -> anyhow::Result<()> {
+        // Arrange
+        let index = SectionIndex::<u8>::read(SAMPLE.as_bytes())?;
+
+        // Act
+        let plan = ReassignmentPlan::new(&index.pairs)?;
+
+        // Assert
+        assert_eq!(
+            calculate_count_of_overlapping_at_all_pairs(plan.corrected_input().as_bytes())?,
+            0
+        );
+        for (before, after) in plan.before.iter().zip(&plan.after) {
+            assert_eq!(
+                before.0.union(before.1),
+                after.0.union(after.1),
+                "{before} -> {after}"
+            );
+        }
+        assert_eq!(
+            plan.changes()
+                .map(|(index, _, after)| format!("{index}: {after}"))
+                .collect::<Vec<_>>(),
+            vec!["2: 5-6,7-9", "3: 2-4,5-8", "4: 6-6,4-5", "5: 2-4,5-8"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reassignment_moves_elves_sharing_one_section() -> anyhow::Result<()> {
+        // Arrange
+        let pairs = [
+            ElfPair::<u8>::from_str("5-5,5-5")?,
+            ElfPair::from_str("6-9,1-2")?,
+        ];
+
+        // Act
+        let plan = ReassignmentPlan::new(&pairs)?;
+
+        // Assert
+        assert_eq!(plan.corrected_input(), "5-5,6-6\n6-9,1-2\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_diff_names_both_files() -> anyhow::Result<()> {
+        // Arrange
+        let pairs = [
+            ElfPair::<u8>::from_str("5-5,5-5")?,
+            ElfPair::from_str("6-9,1-2")?,
+        ];
+        let plan = ReassignmentPlan::new(&pairs)?;
+
         // Act
-        let count = calculate_count_of_fully_containing_pairs(TEST_INPUT)?;
+        let diff = PlanDiff {
+            plan: &plan,
+            from: Path::new("inputs/2022/day04.input"),
+            to: Path::new("corrected.input"),
+        }
+        .to_string();
 
         // Assert
-        assert_eq!(count, 2);
+        assert_eq!(
+            diff,
+            "--- inputs/2022/day04.input\n\
+             +++ corrected.input\n\
+             @@ -1 +1 @@\n\
+             -5-5,5-5\n\
+             +5-5,6-6\n\
+             1 of 2 pairs reassigned, 2 sections changed\n"
+        );
 
         Ok(())
     }
 
     #[test]
-    fn default_test_part_2() -> anyhow::Result<()> {
+    fn test_coverage_analysis() -> anyhow::Result<()> {
         // Act
-        let count = calculate_count_of_overlapping_at_all_pairs(TEST_INPUT)?;
+        let coverage = CoverageAnalysis::new(&SectionIndex::<u16>::read(SAMPLE.as_bytes())?);
 
         // Assert
-        assert_eq!(count, 4);
+        assert_eq!(coverage.pairs, 6);
+        assert_eq!(coverage.assigned.to_string(), "2-9");
+        assert_eq!(coverage.assigned.gaps().to_string(), "none");
+        assert_eq!(coverage.max_coverage, 8);
+        assert_eq!(coverage.most_covered.to_string(), "6-6");
+        assert_eq!(coverage.redundant_sections, 1 + 5 + 1 + 3);
 
         Ok(())
     }
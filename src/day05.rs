@@ -1,19 +1,23 @@
+use std::fmt::Display;
 use std::ops::Div;
 use std::str::FromStr;
 
 use anyhow::Context;
 
-use regex::{Captures, Regex};
-
-const INPUT: &str = include_str!("../inputs/day05.input");
+use aoc_2022::parse::{self, Cursor, ParseError, ParseResult};
 
 fn main() -> anyhow::Result<()> {
+    let args = aoc_2022::cli::DayArgs::init()?;
+    let input = args.input(2022, "day05")?;
+
     // PART 1 - 1 hour 37 minutes 4 seconds
-    let crane_message_1 = calculate_crane_message_with_crane_mover_9000(INPUT)?;
+    let crane_message_1 = tracing::info_span!("part", number = 1)
+        .in_scope(|| calculate_crane_message_with_crane_mover_9000(&input))?;
     println!("crane_message_1: {crane_message_1}");
 
     // PART 2 - 4 minutes 27 seconds
-    let crane_message_2 = calculate_crane_message_with_crane_mover_9001(INPUT)?;
+    let crane_message_2 = tracing::info_span!("part", number = 2)
+        .in_scope(|| calculate_crane_message_with_crane_mover_9001(&input))?;
     println!("crane_message_2: {crane_message_2}");
 
     Ok(())
@@ -71,6 +75,7 @@ impl RearrangementProcedure {
             Err(anyhow::anyhow!("No procedure steps left."))
         } else {
             let next_procedure = self.procedure_steps.remove(0);
+            tracing::debug!(?next_procedure, "applying procedure step");
 
             let source_stack_index =
                 usize::from(next_procedure.from)
@@ -118,23 +123,24 @@ impl RearrangementProcedure {
 impl FromStr for RearrangementProcedure {
     type Err = anyhow::Error;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let both_parts: [&str; 2] =
-            s.split("\n\n")
-                .collect::<Vec<&str>>()
-                .try_into()
-                .map_err(|vec: Vec<_>| {
-                    anyhow::anyhow!("Split string is not 2 parts but {} ({:?})", vec.len(), vec)
-                })?;
+        let [stacks, mut procedure_steps]: [Cursor<'_>; 2] = parse::all(s, |cursor| {
+            cursor.blocks().try_into().map_err(|blocks: Vec<_>| {
+                cursor.error(format!("expected 2 blocks, found {}", blocks.len()))
+            })
+        })?;
         Ok(Self {
-            stacks: Stacks::from_str(both_parts[0])?,
-            procedure_steps: both_parts[1]
+            stacks: Stacks::from_str(stacks.rest()).context("while parsing the stacks")?,
+            procedure_steps: procedure_steps
                 .lines()
-                .enumerate()
-                .map(|(index, line)| {
-                    ProcedureStep::from_str(line).with_context(|| format!("on line #{index}"))
+                .into_iter()
+                .map(|mut line| {
+                    let step = ProcedureStep::parse(&mut line)?;
+                    line.finish()?;
+                    Ok(step)
                 })
-                .collect::<Result<Vec<_>, _>>()?,
+                .collect::<ParseResult<Vec<_>>>()?,
         })
     }
 }
@@ -143,6 +149,12 @@ impl FromStr for RearrangementProcedure {
 struct Stacks(Vec<Vec<char>>);
 
 impl Stacks {
+    /// Characters of a column in the drawing of `stack_count` stacks, wide enough for a crate and
+    /// for the largest stack number.
+    fn column_width(stack_count: usize) -> usize {
+        stack_count.to_string().len().max(3)
+    }
+
     fn get_stack_with_raw_index_mut(&mut self, index: usize) -> anyhow::Result<&mut Vec<char>> {
         self.0
             .get_mut(index)
@@ -157,10 +169,46 @@ impl Stacks {
     }
 }
 
+/// The drawing [`Stacks::from_str`] reads: one line per crate level from the top, then the stack
+/// numbers, every line padded to the width of all stacks.
+///
+/// Columns are three characters wide like in the puzzle, or wider if the stack numbers need it.
+impl Display for Stacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = Self::column_width(self.0.len());
+        let height = self.0.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            for (index, stack) in self.0.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(" ")?;
+                }
+                match stack.get(level) {
+                    Some(single_crate) => write!(f, "{:<width$}", format!("[{single_crate}]"))?,
+                    None => write!(f, "{:width$}", "")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        for index in 0..self.0.len() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{:^width$}", index.saturating_add(1))?;
+        }
+        Ok(())
+    }
+}
+
 impl FromStr for Stacks {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let stack_count = s
+            .lines()
+            .last()
+            .map_or(0, |numbers| numbers.split_whitespace().count());
+        let width = Self::column_width(stack_count);
+        let pitch = width.saturating_add(1);
         Ok(Self (
             s
                 .lines()
@@ -170,9 +218,9 @@ impl FromStr for Stacks {
 
                     let element_count = crates_in_line
                         .len()
-                        .checked_sub(3)
+                        .checked_sub(width)
                         .ok_or_else(|| anyhow::anyhow!("Stack line #{index} is empty."))?
-                        .div(4)
+                        .div(pitch)
                         .checked_add(1)
                         .ok_or_else(|| {
                             anyhow::anyhow!("Stack line #{index} has too many elements.")
@@ -181,7 +229,7 @@ impl FromStr for Stacks {
                     (0..element_count)
                         .map(|element_index| {
                             let stack_begin_index = element_index
-                                .checked_mul(4)
+                                .checked_mul(pitch)
                                 .ok_or_else(|| anyhow::anyhow!("Stack line #{index} is too long."))?;
                             let stack_first_char = *crates_in_line
                                 .get(stack_begin_index)
@@ -204,21 +252,19 @@ impl FromStr for Stacks {
                 .collect::<anyhow::Result<Vec<_>>>()?
                 .into_iter()
                 .rev()
-                .fold(Ok(Vec::new()), |mut stacks: anyhow::Result<Vec<Vec<char>>>, crate_line| {
-                    if let Ok(ref mut ok_stacks) = stacks {
-                        while ok_stacks.len() < crate_line.len() {
-                            ok_stacks.push(Vec::new());
-                        }
-                        for (index, optional_crate) in crate_line.into_iter().enumerate() {
-                            if let Some(single_crate) = optional_crate {
-                                ok_stacks
-                                    .get_mut(index)
-                                    .ok_or_else(|| anyhow::anyhow!("Did not find stack index #{index}."))?
-                                    .push(single_crate);
-                            }
+                .try_fold(Vec::new(), |mut stacks: Vec<Vec<char>>, crate_line| {
+                    while stacks.len() < crate_line.len() {
+                        stacks.push(Vec::new());
+                    }
+                    for (index, optional_crate) in crate_line.into_iter().enumerate() {
+                        if let Some(single_crate) = optional_crate {
+                            stacks
+                                .get_mut(index)
+                                .ok_or_else(|| anyhow::anyhow!("Did not find stack index #{index}."))?
+                                .push(single_crate);
                         }
                     }
-                    stacks
+                    Ok::<_, anyhow::Error>(stacks)
                 })?,
         ))
     }
@@ -231,31 +277,23 @@ struct ProcedureStep {
     to: u8,
 }
 
+impl ProcedureStep {
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        cursor.tag("move ")?;
+        let count = cursor.number()?;
+        cursor.tag(" from ")?;
+        let from = cursor.number()?;
+        cursor.tag(" to ")?;
+        let to = cursor.number()?;
+        Ok(Self { count, from, to })
+    }
+}
+
 impl FromStr for ProcedureStep {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        fn extract_byte_from_capture_group(
-            captures: &Captures,
-            index: usize,
-        ) -> anyhow::Result<u8> {
-            captures
-                .get(index)
-                .ok_or_else(|| anyhow::anyhow!("Missing capture group."))?
-                .as_str()
-                .parse::<u8>()
-                .context("Capture group did not capture a number.")
-        }
-
-        let line_captures = Regex::new("move (\\d+) from (\\d+) to (\\d+)")
-            .context("creating the regex in ProcedureStep::from_str")?
-            .captures(input)
-            .ok_or_else(|| anyhow::anyhow!("Input did not match expected pattern."))?;
-        Ok(Self {
-            count: extract_byte_from_capture_group(&line_captures, 1).context("with `count`")?,
-            from: extract_byte_from_capture_group(&line_captures, 2).context("with `from`")?,
-            to: extract_byte_from_capture_group(&line_captures, 3).context("with `to`")?,
-        })
+        parse::all(input, Self::parse)
     }
 }
 
@@ -264,15 +302,12 @@ impl FromStr for ProcedureStep {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "    [D]
-[N] [C]
-[Z] [M] [P]
- 1   2   3
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day05"],
+        test_part_1_default: "crane_message_1" => calculate_crane_message_with_crane_mover_9000,
+        test_part_2_default: "crane_message_2" => calculate_crane_message_with_crane_mover_9001,
+    }
 
     const TEST_PROCEDURE_STEPS: [ProcedureStep; 4] = [
         ProcedureStep {
@@ -297,17 +332,6 @@ move 1 from 1 to 2";
         },
     ];
 
-    #[test]
-    fn test_part_1_default() -> anyhow::Result<()> {
-        // Act
-        let result = calculate_crane_message_with_crane_mover_9000(TEST_INPUT)?;
-
-        // Assert
-        assert_eq!(result, "CMZ");
-
-        Ok(())
-    }
-
     #[test]
     fn test_procedure_step_from_str() -> anyhow::Result<()> {
         // Arrange
@@ -348,6 +372,93 @@ move 1 from 1 to 2";
         Ok(())
     }
 
+    #[test]
+    fn test_crate_stacks_to_string() {
+        // Arrange
+        let crate_stacks = Stacks(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+
+        // Act
+        let drawing = crate_stacks.to_string();
+
+        // Assert
+        assert_eq!(
+            drawing,
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 "
+        );
+    }
+
+    #[test]
+    fn test_crate_stacks_to_string_with_two_digit_numbers() {
+        // Arrange
+        let crate_stacks = Stacks(
+            (0..12)
+                .map(|index| vec![char::from(b'A'.saturating_add(index))])
+                .collect(),
+        );
+
+        // Act
+        let drawing = crate_stacks.to_string();
+
+        // Assert
+        assert_eq!(
+            drawing,
+            "[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [K] [L]\n 1   2   3   4   5   6   7   8   9  10  11  12 "
+        );
+    }
+
+    #[test]
+    fn test_crate_stacks_to_string_widens_columns_for_four_digit_numbers() {
+        // Arrange
+        let mut stacks = vec![Vec::new(); 1000];
+        if let Some(first) = stacks.first_mut() {
+            first.push('A');
+        }
+        let crate_stacks = Stacks(stacks);
+
+        // Act
+        let drawing = crate_stacks.to_string();
+        let (crates, numbers) = drawing.split_once('\n').unwrap_or_default();
+
+        // Assert
+        assert_eq!(crates.len(), 1000 * 5 - 1);
+        assert!(crates.starts_with("[A]      "));
+        assert!(numbers.starts_with(" 1    2    3  "));
+        assert!(numbers.contains(" 99  100  101 "));
+        assert!(numbers.ends_with("998  999  1000"));
+    }
+
+    #[test]
+    fn test_crate_stacks_round_trip() -> anyhow::Result<()> {
+        // Arrange
+        let wide = Stacks(
+            (0..12_u8)
+                .map(|index| {
+                    (0..index.rem_euclid(5))
+                        .map(|height| char::from(b'A'.saturating_add(index).saturating_add(height)))
+                        .collect()
+                })
+                .collect(),
+        );
+        let tall = Stacks(vec![vec![], ('a'..='z').collect(), vec!['['], vec![]]);
+        let widened = Stacks(
+            (0..1000_usize)
+                .map(|index| "abc".chars().take(index.rem_euclid(3)).collect())
+                .collect(),
+        );
+        let empty = Stacks(vec![vec![]; 3]);
+
+        for crate_stacks in [wide, tall, widened, empty, Stacks(Vec::new())] {
+            // Act
+            let drawing = crate_stacks.to_string();
+            let parsed = Stacks::from_str(&drawing)?;
+
+            // Assert
+            assert_eq!(parsed, crate_stacks, "{drawing}");
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_rearrangement_procedure() -> anyhow::Result<()> {
         // Arrange
@@ -1,17 +1,33 @@
-use itertools::Itertools;
+use std::io::BufRead;
+
+use anyhow::Context;
 
-const INPUT: &str = include_str!("../inputs/day06.input");
+use itertools::Itertools;
 
 fn main() -> anyhow::Result<()> {
+    let args = aoc_2022::cli::DayArgs::init()?;
+
     // PART 1 - 14 minutes 10 seconds
-    let part_1_solution = calculate_last_index_of_first_non_repeating_char_sequence(INPUT, 4)
+    let part_1_solution = tracing::info_span!("part", number = 1)
+        .in_scope(|| {
+            calculate_last_index_of_first_non_repeating_char_sequence(
+                args.input_reader(2022, "day06")?,
+                4,
+            )
+        })?
         .ok_or_else(|| {
             anyhow::anyhow!("Did not find a non repeating char sequence with length of 4")
         })?;
     println!("part_1_solution: {part_1_solution}");
 
     // PART 2 - 2 minutes 39 seconds
-    let part_2_solution = calculate_last_index_of_first_non_repeating_char_sequence(INPUT, 14)
+    let part_2_solution = tracing::info_span!("part", number = 2)
+        .in_scope(|| {
+            calculate_last_index_of_first_non_repeating_char_sequence(
+                args.input_reader(2022, "day06")?,
+                14,
+            )
+        })?
         .ok_or_else(|| {
             anyhow::anyhow!("Did not find a non repeating char sequence with length of 14")
         })?;
@@ -20,12 +36,14 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Reads the datastream one byte at a time, as it is a single line that may not fit into memory.
 fn calculate_last_index_of_first_non_repeating_char_sequence(
-    input: &str,
+    reader: impl BufRead,
     sequence_length: usize,
-) -> Option<usize> {
+) -> anyhow::Result<Option<usize>> {
     let mut ring_buffer = Vec::with_capacity(sequence_length);
-    for (index, char) in input.chars().enumerate() {
+    for (index, byte) in reader.bytes().enumerate() {
+        let char = byte.with_context(|| format!("while reading character #{index}"))?;
         if ring_buffer.len() == sequence_length {
             // @lint: Don't know how to do that and whether it really benefits.
             #[allow(clippy::pattern_type_mismatch)]
@@ -36,56 +54,30 @@ fn calculate_last_index_of_first_non_repeating_char_sequence(
                 .filter(|((index_a, _), (index_b, _))| index_a != index_b && index_a < index_b)
                 .all(|((_, character_a), (_, character_b))| character_a != character_b);
             if all_unequal {
-                return Some(index);
+                tracing::debug!(index, sequence_length, "found non repeating sequence");
+                return Ok(Some(index));
             }
             ring_buffer.remove(0);
         }
         ring_buffer.push(char);
     }
-    None
+    Ok(None)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part_1_default() {
-        // Arrange
-        let test_inputs: [(&str, usize); 5] = [
-            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7),
-            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5),
-            ("nppdvjthqldpwncqszvftbrmjlhg", 6),
-            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10),
-            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11),
-        ];
-
-        for (test_input, test_target_output) in test_inputs {
-            // Act
-            let output = calculate_last_index_of_first_non_repeating_char_sequence(test_input, 4);
-
-            // Assert
-            assert_eq!(output, Some(test_target_output), "during {test_input}");
-        }
-    }
-
-    #[test]
-    fn test_part_2_default() {
-        // Arrange
-        let test_inputs: [(&str, usize); 5] = [
-            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 19),
-            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 23),
-            ("nppdvjthqldpwncqszvftbrmjlhg", 23),
-            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 29),
-            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 26),
-        ];
-
-        for (test_input, test_target_output) in test_inputs {
-            // Act
-            let output = calculate_last_index_of_first_non_repeating_char_sequence(test_input, 14);
-
-            // Assert
-            assert_eq!(output, Some(test_target_output), "during {test_input}");
-        }
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day06", "day06-2", "day06-3", "day06-4", "day06-5"],
+        test_part_1_default: "part_1_solution" => |input| {
+            calculate_last_index_of_first_non_repeating_char_sequence(input.as_bytes(), 4)?
+                .ok_or_else(|| anyhow::anyhow!("Did not find a non repeating char sequence."))
+        },
+        test_part_2_default: "part_2_solution" => |input| {
+            calculate_last_index_of_first_non_repeating_char_sequence(input.as_bytes(), 14)?
+                .ok_or_else(|| anyhow::anyhow!("Did not find a non repeating char sequence."))
+        },
     }
 }
@@ -5,21 +5,28 @@ use std::str::FromStr;
 
 use anyhow::Context;
 
-const INPUT: &str = include_str!("../inputs/day07.input");
+use aoc_2022::parse::{self, Cursor, ParseError, ParseResult};
 
 fn main() -> anyhow::Result<()> {
-    let derived_filesystem = CommandHistory::from_str(INPUT)?
+    let args = aoc_2022::cli::DayArgs::init()?;
+    let input = args.input(2022, "day07")?;
+
+    let derived_filesystem = CommandHistory::from_str(&input)?
         .derive_filesystem()?
         .ok_or_else(|| anyhow::anyhow!("No filesystem found."))?;
+    args.render("filesystem", || derived_filesystem.to_string())?;
 
     // PART 1 - 1 hour 26 minutes 53 seconds
-    let part_1_solution =
-        derived_filesystem.calculate_sum_of_directories_sizes_where_each_size_max(100_000);
+    let part_1_solution = tracing::info_span!("part", number = 1).in_scope(|| {
+        derived_filesystem.calculate_sum_of_directories_sizes_where_each_size_max(100_000)
+    });
     println!("part_1_solution: {part_1_solution}");
 
     // PART 2 - 10 minutes 10 seconds
-    let part_2_solution = derived_filesystem
-        .find_directory_size_to_delete_to_free_enough_space(70_000_000, 30_000_000)?;
+    let part_2_solution = tracing::info_span!("part", number = 2).in_scope(|| {
+        derived_filesystem
+            .find_directory_size_to_delete_to_free_enough_space(70_000_000, 30_000_000)
+    })?;
     println!("part_2_solution: {part_2_solution}");
 
     Ok(())
@@ -28,6 +35,7 @@ fn main() -> anyhow::Result<()> {
 struct CommandHistory(Vec<ExecutedCommand>);
 
 impl CommandHistory {
+    #[tracing::instrument(skip_all)]
     fn derive_filesystem(&self) -> anyhow::Result<Option<Filesystem>> {
         let starting_directory_name = match self.0.first() {
             Some(executed_command) => executed_command
@@ -47,6 +55,7 @@ impl CommandHistory {
         let mut current_filesystem_element: Rc<RefCell<FilesystemElement>> =
             Rc::clone(&filesystem.0);
         for (executed_index, executed_command) in self.0.iter().enumerate().skip(1) {
+            tracing::trace!(executed_index, "replaying executed command");
             match executed_command.command {
                 Command::ChangeDirectory { ref target } => {
                     if target == ".." {
@@ -85,26 +94,17 @@ impl CommandHistory {
                 }
                 Command::ListDirectoryContents => {
                     let children = executed_command
-                        .output_lines
+                        .listing
                         .iter()
-                        .enumerate()
-                        .map(|(line_index, output_line)| output_line
-                            .split(' ')
-                            .collect::<Vec<_>>()
-                            .try_into()
-                            .map_err(|vec: Vec<_>| anyhow::anyhow!("Could not split line #{line_index} of executed command #{executed_index} into two parts (splitted into {} parts).", vec.len())))
-                        .collect::<Result<Vec<[&str; 2]>, _>>()?
-                        .into_iter()
-                        .enumerate()
-                        .map(|(line_index, line_components)| if line_components[0] == "dir" {
-                            Ok(FilesystemElement::new_directory(line_components[1].to_owned()))
-                        } else {
-                            line_components[0]
-                                .parse::<usize>()
-                                .with_context(|| anyhow::anyhow!("while parsing number of line #{line_index} \"{line_components:?}\" in executed command #{executed_index}"))
-                                .map(|file_size| FilesystemElement::new_file(line_components[1].to_owned(), file_size))
+                        .map(|listed| match *listed {
+                            ListedElement::Directory { ref name } => {
+                                FilesystemElement::new_directory(name.clone())
+                            }
+                            ListedElement::File { ref name, size } => {
+                                FilesystemElement::new_file(name.clone(), size)
+                            }
                         })
-                        .collect::<Result<Vec<_>, _>>()?;
+                        .collect();
                     let parent_ref = Rc::downgrade(&current_filesystem_element);
                     RefCell::borrow_mut(&current_filesystem_element)
                         .add_children(children, &parent_ref)?;
@@ -116,44 +116,42 @@ impl CommandHistory {
     }
 }
 
+impl CommandHistory {
+    /// Each line starting with `$ ` is a command, all other lines list the output of the previous.
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        let mut executed_commands: Vec<ExecutedCommand> = Vec::new();
+        for mut line in cursor.lines() {
+            if line.try_tag("$ ") {
+                executed_commands.push(ExecutedCommand {
+                    command: Command::parse(&mut line)?,
+                    listing: Vec::new(),
+                });
+            } else {
+                let listed = ListedElement::parse(&mut line)?;
+                executed_commands
+                    .last_mut()
+                    .ok_or_else(|| line.error("expected a command before its output"))?
+                    .listing
+                    .push(listed);
+            }
+            line.finish()?;
+        }
+        Ok(Self(executed_commands))
+    }
+}
+
 impl FromStr for CommandHistory {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(
-            s.split('$')
-                .skip(1)
-                .enumerate()
-                .map(|(index, executed_command_string)| {
-                    ExecutedCommand::from_str(executed_command_string)
-                        .with_context(|| format!("for executed command #{index}"))
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        ))
+        parse::all(s, Self::parse)
     }
 }
 
 struct ExecutedCommand {
     command: Command,
-    output_lines: Vec<String>,
-}
-
-impl FromStr for ExecutedCommand {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let command = lines
-            .next()
-            .map(str::trim)
-            .map(Command::from_str)
-            .ok_or_else(|| anyhow::anyhow!("Executed command did not have first line."))?
-            .with_context(|| format!("for executed command \"{s}\""))?;
-        Ok(Self {
-            command,
-            output_lines: lines.map(str::to_owned).collect(),
-        })
-    }
+    listing: Vec<ListedElement>,
 }
 
 enum Command {
@@ -168,20 +166,38 @@ impl Command {
             Command::ListDirectoryContents => None,
         }
     }
-}
-
-impl FromStr for Command {
-    type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some(target) = s.strip_prefix("cd ") {
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        if cursor.try_tag("cd ") {
             Ok(Self::ChangeDirectory {
-                target: target.to_owned(),
+                target: cursor.rest_of_line().to_owned(),
             })
-        } else if s == "ls" {
+        } else {
+            cursor.tag("ls")?;
             Ok(Self::ListDirectoryContents)
+        }
+    }
+}
+
+/// One line of the output of `ls`.
+enum ListedElement {
+    Directory { name: String },
+    File { name: String, size: usize },
+}
+
+impl ListedElement {
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        if cursor.try_tag("dir ") {
+            Ok(Self::Directory {
+                name: cursor.rest_of_line().to_owned(),
+            })
         } else {
-            Err(anyhow::anyhow!("Did not recognize \"{s}\"."))
+            let size = cursor.number()?;
+            cursor.tag(" ")?;
+            Ok(Self::File {
+                name: cursor.rest_of_line().to_owned(),
+                size,
+            })
         }
     }
 }
@@ -417,45 +433,25 @@ impl Display for FilesystemElement {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
-
-    #[test]
-    fn test_part_1_default() -> anyhow::Result<()> {
-        // Act
-        let sum_of_directories_with_total_size_at_most_100_000 =
-            CommandHistory::from_str(TEST_INPUT)?
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day07"],
+        test_part_1_default: "part_1_solution" => |input| {
+            Ok(CommandHistory::from_str(input)?
                 .derive_filesystem()?
                 .ok_or_else(|| anyhow::anyhow!("No filesystem found."))?
-                .calculate_sum_of_directories_sizes_where_each_size_max(100_000);
-
-        // Assert
-        assert_eq!(sum_of_directories_with_total_size_at_most_100_000, 95_437);
-
-        Ok(())
+                .calculate_sum_of_directories_sizes_where_each_size_max(100_000))
+        },
+        test_part_2_default: "part_2_solution" => |input| {
+            CommandHistory::from_str(input)?
+                .derive_filesystem()?
+                .ok_or_else(|| anyhow::anyhow!("No filesystem found."))?
+                .find_directory_size_to_delete_to_free_enough_space(70_000_000, 30_000_000)
+        },
     }
 
+    const TEST_INPUT: &str = include_str!("../samples/2022/day07.input");
+
     #[test]
     fn test_part_1_visual_representation() -> anyhow::Result<()> {
         // Arrange
@@ -484,21 +480,4 @@ $ ls
 
         Ok(())
     }
-
-    #[test]
-    fn test_part_2_default() -> anyhow::Result<()> {
-        // Arrange
-        let derived_filesystem = CommandHistory::from_str(TEST_INPUT)?
-            .derive_filesystem()?
-            .ok_or_else(|| anyhow::anyhow!("No filesystem found."))?;
-
-        // Act
-        let total_size_of_deletable_directory = derived_filesystem
-            .find_directory_size_to_delete_to_free_enough_space(70_000_000, 30_000_000)?;
-
-        // Assert
-        assert_eq!(total_size_of_deletable_directory, 24_933_642);
-
-        Ok(())
-    }
 }
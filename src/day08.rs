@@ -6,23 +6,30 @@ use anyhow::Context;
 use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../inputs/day08.input");
-
 fn main() -> anyhow::Result<()> {
-    let tree_map = TreeMap::from_str(INPUT)?;
+    let args = aoc_2022::cli::DayArgs::init()?;
+    let input = args.input(2022, "day08")?;
+
+    let tree_map = TreeMap::from_str(&input)?;
 
     // PART 1 - 46 minutes 27 seconds
-    let part_1_solution = tree_map.calculate_visibility_map()?.count_visible_fields();
+    let visibility_map =
+        tracing::info_span!("part", number = 1).in_scope(|| tree_map.calculate_visibility_map())?;
+    let part_1_solution = visibility_map.count_visible_fields();
     println!("part_1_solution: {part_1_solution}");
+    args.render("tree-heights", || tree_map.to_string())?;
+    args.render("visibility", || visibility_map.to_string())?;
 
     // PART 2 - 29 minutes 48 seconds
-    let part_2_solution = tree_map
-        .calculate_scenic_score_map()
-        .context("while calculating the scenic score map for part 2")?
+    let scenic_score_map = tracing::info_span!("part", number = 2)
+        .in_scope(|| tree_map.calculate_scenic_score_map())
+        .context("while calculating the scenic score map for part 2")?;
+    let part_2_solution = scenic_score_map
         .find_highest_scenic_score()
         .copied()
         .ok_or_else(|| anyhow::anyhow!("No trees were in given area."))?;
     println!("part_2_solution: {part_2_solution}");
+    args.render("scenic-scores", || scenic_score_map.to_string())?;
 
     Ok(())
 }
@@ -246,6 +253,7 @@ impl TreeMap {
 impl FromStr for TreeMap {
     type Err = anyhow::Error;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let tree_map = s
             .lines()
@@ -317,6 +325,25 @@ impl VisibilityMap {
     }
 }
 
+/// Visible trees as `#` and hidden ones as `.`.
+impl Display for VisibilityMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (row_index, row) in self.0.iter().enumerate() {
+            if row_index > 0 {
+                writeln!(f)?;
+            }
+            for visibility in row {
+                let symbol = match *visibility {
+                    Visibility::Visible => '#',
+                    Visibility::Invisible => '.',
+                };
+                write!(f, "{symbol}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 enum Visibility {
     Visible,
@@ -332,6 +359,36 @@ impl ScenicScoreMap {
     }
 }
 
+/// Heat map of the scenic scores on a logarithmic scale, from ` ` for none to `@` for the highest.
+impl Display for ScenicScoreMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const RAMP: &[u8] = b" .:-=+*#%@";
+        let magnitude = |score: &ScenicScore| {
+            score
+                .0
+                .checked_ilog2()
+                .map_or(0, |log| log.saturating_add(1))
+        };
+        let highest = self.find_highest_scenic_score().map_or(0, magnitude);
+        let steps = u32::try_from(RAMP.len().saturating_sub(1)).unwrap_or(0);
+        for (row_index, row) in self.0.iter().enumerate() {
+            if row_index > 0 {
+                writeln!(f)?;
+            }
+            for score in row {
+                let level = magnitude(score)
+                    .saturating_mul(steps)
+                    .checked_div(highest)
+                    .and_then(|level| usize::try_from(level).ok())
+                    .unwrap_or(0);
+                let symbol = RAMP.get(level).copied().unwrap_or(b'@');
+                write!(f, "{}", char::from(symbol))?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Ord, PartialOrd)]
 struct ScenicScore(u64);
 
@@ -347,26 +404,25 @@ impl Display for ScenicScore {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "30373
-25512
-65332
-33549
-35390";
-
-    #[test]
-    fn test_part_1_default() -> anyhow::Result<()> {
-        // Arrange
-        let tree_map = TreeMap::from_str(TEST_INPUT)?;
-
-        // Act
-        let visible_trees = tree_map.calculate_visibility_map()?.count_visible_fields();
-
-        // Assert
-        assert_eq!(visible_trees, 21);
-
-        Ok(())
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day08"],
+        test_part_1_default: "part_1_solution" => |input| {
+            Ok(TreeMap::from_str(input)?
+                .calculate_visibility_map()?
+                .count_visible_fields())
+        },
+        test_part_2_default: "part_2_solution" => |input| {
+            TreeMap::from_str(input)?
+                .calculate_scenic_score_map()?
+                .find_highest_scenic_score()
+                .copied()
+                .ok_or_else(|| anyhow::anyhow!("No trees were in given area."))
+        },
     }
 
+    const TEST_INPUT: &str = include_str!("../samples/2022/day08.input");
+
     #[test]
     fn test_tree_map_from_str() -> anyhow::Result<()> {
         // Act
@@ -397,7 +453,7 @@ mod tests {
         let tree_map_string = tree_map.to_string();
 
         // Assert
-        assert_eq!(tree_map_string, TEST_INPUT);
+        assert_eq!(tree_map_string, TEST_INPUT.trim_end());
 
         Ok(())
     }
@@ -429,7 +485,7 @@ mod tests {
     }
 
     #[test]
-    fn test_part_2_default() -> anyhow::Result<()> {
+    fn test_tree_map_calculate_scenic_score_map() -> anyhow::Result<()> {
         // Arrange
         let tree_map = TreeMap::from_str(TEST_INPUT)?;
 
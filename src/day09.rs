@@ -1,46 +1,44 @@
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::iter::Enumerate;
 use std::str::FromStr;
 
 use anyhow::Context;
 
-use itertools::Itertools;
-
-const INPUT: &str = include_str!("../inputs/day09.input");
+use aoc_2022::grid::{Position, Vector};
+use aoc_2022::stream::Lines;
 
 fn main() -> anyhow::Result<()> {
-    let motion_series = MotionSeries::from_str(INPUT)?;
+    let args = aoc_2022::cli::DayArgs::init()?;
 
     // PART 1 - 1 hour 21 minutes 33 seconds
-    let rope_states_1 = RopeState::<0>::default()
-        .apply_motion_series_return_with_you(&motion_series)
-        .context("while applying motion series to 2 knots rope.")?;
-    let part_1_solution = count_unique_visited_tail_positions(&rope_states_1);
+    let part_1_solution = tracing::info_span!("part", number = 1).in_scope(|| {
+        RopeState::<0>::default()
+            .count_unique_visited_tail_positions(MotionSeries::new(
+                args.input_reader(2022, "day09")?,
+            ))
+            .context("while applying motion series to 2 knots rope.")
+    })?;
     println!("part_1_solution: {part_1_solution}");
 
     // PART 2 - 17 minutes 54 seconds
-    let rope_states_2 = RopeState::<8>::default()
-        .apply_motion_series_return_with_you(&motion_series)
-        .context("while applying motion series to 10 knots rope.")?;
-    let part_2_solution = count_unique_visited_tail_positions(&rope_states_2);
+    let part_2_solution = tracing::info_span!("part", number = 2).in_scope(|| {
+        RopeState::<8>::default()
+            .count_unique_visited_tail_positions(MotionSeries::new(
+                args.input_reader(2022, "day09")?,
+            ))
+            .context("while applying motion series to 10 knots rope.")
+    })?;
     println!("part_2_solution: {part_2_solution}");
 
     Ok(())
 }
 
-fn count_unique_visited_tail_positions<const ADDITIONAL: usize>(
-    rope_states: &[RopeState<ADDITIONAL>],
-) -> usize {
-    rope_states
-        .iter()
-        .map(|rope_state| rope_state.tail_position)
-        .unique()
-        .count()
-}
-
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 struct RopeState<const ADDITIONAL_KNOTS: usize = 0> {
-    head_position: Position2D,
-    between_positions: [Position2D; ADDITIONAL_KNOTS],
-    tail_position: Position2D,
+    head_position: Position,
+    between_positions: [Position; ADDITIONAL_KNOTS],
+    tail_position: Position,
 }
 
 impl<const ADDITIONAL_KNOTS: usize> RopeState<ADDITIONAL_KNOTS> {
@@ -63,14 +61,13 @@ impl<const ADDITIONAL_KNOTS: usize> RopeState<ADDITIONAL_KNOTS> {
                     )
                 })?;
 
-            let mut new_between_positions: [Position2D; ADDITIONAL_KNOTS] =
-                current.between_positions;
+            let mut new_between_positions: [Position; ADDITIONAL_KNOTS] = current.between_positions;
             for (index, current_position) in current.between_positions.iter().enumerate() {
-                let prior_position: Position2D = *index
+                let prior_position: Position = *index
                     .checked_sub(1)
                     .and_then(|prior_index| new_between_positions.get(prior_index))
                     .unwrap_or(&new_head_position);
-                let new_between_position: &mut Position2D = new_between_positions
+                let new_between_position: &mut Position = new_between_positions
                     .get_mut(index)
                     .ok_or_else(|| anyhow::anyhow!("Could not index into array with length {ADDITIONAL_KNOTS} with index {index}."))?;
                 *new_between_position = move_b_one_closer_to_a(&prior_position, current_position)
@@ -100,58 +97,56 @@ impl<const ADDITIONAL_KNOTS: usize> RopeState<ADDITIONAL_KNOTS> {
         Ok(output)
     }
 
-    fn apply_motion_series_return_with_you(
+    /// Applies the motions as they are read, remembering only the visited tail positions.
+    fn count_unique_visited_tail_positions(
         &self,
-        motion_series: &MotionSeries,
-    ) -> anyhow::Result<Vec<Self>> {
-        let capacity = motion_series.0.len().checked_add(1).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Could not create output vector, there are too many motions (1 + {})",
-                motion_series.0.len()
-            )
-        })?;
-        let mut output = Vec::with_capacity(capacity);
-
-        output.push(*self);
-
+        motion_series: impl Iterator<Item = anyhow::Result<Motion>>,
+    ) -> anyhow::Result<usize> {
+        let mut visited_tail_positions = HashSet::from([self.tail_position]);
         let mut current = *self;
-        for motion in &motion_series.0 {
+        for motion in motion_series {
+            let motion = motion?;
+            tracing::trace!(?motion, "applying motion");
             let rope_states = current
-                .apply_motion(motion)
+                .apply_motion(&motion)
                 .with_context(|| format!("while applying {motion:?} to {current:?}"))?;
-            output.extend_from_slice(&rope_states);
+            visited_tail_positions.extend(
+                rope_states
+                    .iter()
+                    .map(|rope_state| rope_state.tail_position),
+            );
             current = *rope_states
                 .last()
                 .ok_or_else(|| anyhow::anyhow!("Returned rope states after {motion:?} has been applied to {current:?} are empty."))?;
         }
-        Ok(output)
+        Ok(visited_tail_positions.len())
     }
 }
 
 fn move_b_one_closer_to_a(
-    position_a: &Position2D,
-    position_b: &Position2D,
-) -> anyhow::Result<Position2D> {
+    position_a: &Position,
+    position_b: &Position,
+) -> anyhow::Result<Position> {
     fn checked_add(a: i64, b: i64, element_str: &str) -> anyhow::Result<i64> {
         a.checked_add(b)
             .ok_or_else(|| anyhow::anyhow!("Could not add {a} to {b} for {element_str}."))
     }
 
-    let vector = position_b.vector_to(position_a).with_context(|| {
-        anyhow::anyhow!("while calculating vector from {position_b:?} to {position_a:?}")
+    let vector = position_b.vector_to(*position_a).ok_or_else(|| {
+        anyhow::anyhow!("Distance from {position_b:?} to {position_a:?} is too big.")
     })?;
     if vector.x == 0 && vector.y.abs() > 1 {
-        Ok(Position2D {
+        Ok(Position {
             x: position_b.x,
             y: checked_add(position_b.y, vector.y.signum(), "Y")?,
         })
     } else if vector.x.abs() > 1 && vector.y == 0 {
-        Ok(Position2D {
+        Ok(Position {
             x: checked_add(position_b.x, vector.x.signum(), "X")?,
             y: position_b.y,
         })
     } else if vector.x.abs() > 1 || vector.y.abs() > 1 {
-        Ok(Position2D {
+        Ok(Position {
             x: checked_add(position_b.x, vector.x.signum(), "X")?,
             y: checked_add(position_b.y, vector.y.signum(), "Y")?,
         })
@@ -163,60 +158,35 @@ fn move_b_one_closer_to_a(
 impl<const ADDITIONAL: usize> Default for RopeState<ADDITIONAL> {
     fn default() -> Self {
         Self {
-            head_position: Position2D::zero(),
-            between_positions: [Position2D::zero(); ADDITIONAL],
-            tail_position: Position2D::zero(),
+            head_position: Position::default(),
+            between_positions: [Position::default(); ADDITIONAL],
+            tail_position: Position::default(),
         }
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
-struct Position2D {
-    x: i64,
-    y: i64,
+/// Motions read line by line, so that the series is never in memory as a whole.
+struct MotionSeries<R> {
+    lines: Enumerate<Lines<R>>,
 }
 
-impl Position2D {
-    fn zero() -> Self {
-        Self { x: 0, y: 0 }
-    }
-
-    fn vector_to(&self, other: &Self) -> anyhow::Result<Vector2D> {
-        Ok(Vector2D {
-            x: other
-                .x
-                .checked_sub(self.x)
-                .ok_or_else(|| anyhow::anyhow!("X distance of this to other is too big."))?,
-            y: other
-                .y
-                .checked_sub(self.y)
-                .ok_or_else(|| anyhow::anyhow!("Y distance of this to other is too big."))?,
-        })
+impl<R: BufRead> MotionSeries<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            lines: aoc_2022::stream::lines(reader).enumerate(),
+        }
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
-struct Vector2D {
-    x: i64,
-    y: i64,
-}
-
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct MotionSeries(Vec<Motion>);
+impl<R: BufRead> Iterator for MotionSeries<R> {
+    type Item = anyhow::Result<Motion>;
 
-impl FromStr for MotionSeries {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(
-            s.lines()
-                .enumerate()
-                .map(|(index, line)| {
-                    Motion::from_str(line)
-                        .with_context(|| anyhow::anyhow!("while parsing motion line #{index}"))
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        ))
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, line) = self.lines.next()?;
+        Some(line.and_then(|line| {
+            Motion::from_str(&line)
+                .with_context(|| anyhow::anyhow!("while parsing motion line #{index}"))
+        }))
     }
 }
 
@@ -238,28 +208,17 @@ impl Motion {
         }
     }
 
-    fn apply_one(&self, position: &Position2D) -> anyhow::Result<Position2D> {
+    fn apply_one(&self, position: &Position) -> anyhow::Result<Position> {
         let error = || {
             anyhow::anyhow!("Cannot apply motion to this position, because afterwards it would be out of bounds.")
         };
-        match *self {
-            Motion::Right(_) => Ok(Position2D {
-                x: position.x.checked_add(1).ok_or_else(error)?,
-                y: position.y,
-            }),
-            Motion::Left(_) => Ok(Position2D {
-                x: position.x.checked_sub(1).ok_or_else(error)?,
-                y: position.y,
-            }),
-            Motion::Up(_) => Ok(Position2D {
-                x: position.x,
-                y: position.y.checked_add(1).ok_or_else(error)?,
-            }),
-            Motion::Down(_) => Ok(Position2D {
-                x: position.x,
-                y: position.y.checked_sub(1).ok_or_else(error)?,
-            }),
-        }
+        let direction = match *self {
+            Motion::Right(_) => Vector::RIGHT,
+            Motion::Left(_) => Vector::LEFT,
+            Motion::Up(_) => Vector::UP,
+            Motion::Down(_) => Vector::DOWN,
+        };
+        position.checked_add(direction).ok_or_else(error)
     }
 }
 
@@ -292,42 +251,33 @@ impl FromStr for Motion {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
-
-    #[test]
-    fn test_part_1_default() -> anyhow::Result<()> {
-        // Act
-        let motion_series = MotionSeries::from_str(TEST_INPUT)?;
-        let rope_states = RopeState::<0>::default()
-            .apply_motion_series_return_with_you(&motion_series)
-            .context("while applying motion series to two knot rope")?;
-        let count_of_unique_visited_tail_positions =
-            count_unique_visited_tail_positions(&rope_states);
-
-        // Assert
-        assert_eq!(count_of_unique_visited_tail_positions, 13);
-
-        Ok(())
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day09", "day09-2"],
+        test_part_1_default: "part_1_solution" => |input| {
+            RopeState::<0>::default()
+                .count_unique_visited_tail_positions(MotionSeries::new(input.as_bytes()))
+        },
+        test_part_2_default: "part_2_solution" => |input| {
+            RopeState::<8>::default()
+                .count_unique_visited_tail_positions(MotionSeries::new(input.as_bytes()))
+        },
     }
 
+    const TEST_INPUT: &str = include_str!("../samples/2022/day09.input");
+
     #[test]
-    fn test_motion_series_from_str() -> anyhow::Result<()> {
+    fn test_motion_series_new() -> anyhow::Result<()> {
         use Motion::{Down, Left, Right, Up};
 
         // Act
-        let motion_series = MotionSeries::from_str(TEST_INPUT)?;
+        let motion_series =
+            MotionSeries::new(TEST_INPUT.as_bytes()).collect::<anyhow::Result<Vec<_>>>()?;
 
         // Assert
         assert_eq!(
             motion_series,
-            MotionSeries(vec![
+            vec![
                 Right(4),
                 Up(4),
                 Left(3),
@@ -336,7 +286,7 @@ R 2";
                 Down(1),
                 Left(5),
                 Right(2),
-            ])
+            ]
         );
 
         Ok(())
@@ -346,9 +296,9 @@ R 2";
     fn test_rope_state_apply_motion() -> anyhow::Result<()> {
         // Arrange
         let initial_state = RopeState {
-            head_position: Position2D { x: 0, y: 0 },
+            head_position: Position { x: 0, y: 0 },
             between_positions: [],
-            tail_position: Position2D { x: 0, y: 0 },
+            tail_position: Position { x: 0, y: 0 },
         };
 
         // Act
@@ -361,24 +311,24 @@ R 2";
             rope_states_1,
             vec![
                 RopeState {
-                    head_position: Position2D { x: 1, y: 0 },
+                    head_position: Position { x: 1, y: 0 },
                     between_positions: [],
-                    tail_position: Position2D { x: 0, y: 0 }
+                    tail_position: Position { x: 0, y: 0 }
                 },
                 RopeState {
-                    head_position: Position2D { x: 2, y: 0 },
+                    head_position: Position { x: 2, y: 0 },
                     between_positions: [],
-                    tail_position: Position2D { x: 1, y: 0 }
+                    tail_position: Position { x: 1, y: 0 }
                 },
                 RopeState {
-                    head_position: Position2D { x: 3, y: 0 },
+                    head_position: Position { x: 3, y: 0 },
                     between_positions: [],
-                    tail_position: Position2D { x: 2, y: 0 }
+                    tail_position: Position { x: 2, y: 0 }
                 },
                 RopeState {
-                    head_position: Position2D { x: 4, y: 0 },
+                    head_position: Position { x: 4, y: 0 },
                     between_positions: [],
-                    tail_position: Position2D { x: 3, y: 0 }
+                    tail_position: Position { x: 3, y: 0 }
                 }
             ]
         );
@@ -391,12 +341,12 @@ R 2";
         // Arrange
         #[rustfmt::skip]
         let tests = vec![
-            ((Position2D { x: 2, y: 1 }, Position2D { x: 1, y: 1 }), Position2D { x: 1, y: 1 }), // don't move
-            ((Position2D { x: 3, y: 1 }, Position2D { x: 1, y: 1 }), Position2D { x: 2, y: 1 }), // on same
-            ((Position2D { x: 3, y: 1 }, Position2D { x: 1, y: 1 }), Position2D { x: 2, y: 1 }), // one horizontal
-            ((Position2D { x: 1, y: 1 }, Position2D { x: 1, y: 3 }), Position2D { x: 1, y: 2 }), // one vertical
-            ((Position2D { x: 2, y: 3 }, Position2D { x: 1, y: 1 }), Position2D { x: 2, y: 2 }), // one diagonal
-            ((Position2D { x: 3, y: 2 }, Position2D { x: 1, y: 1 }), Position2D { x: 2, y: 2 }), // one diagonal
+            ((Position { x: 2, y: 1 }, Position { x: 1, y: 1 }), Position { x: 1, y: 1 }), // don't move
+            ((Position { x: 3, y: 1 }, Position { x: 1, y: 1 }), Position { x: 2, y: 1 }), // on same
+            ((Position { x: 3, y: 1 }, Position { x: 1, y: 1 }), Position { x: 2, y: 1 }), // one horizontal
+            ((Position { x: 1, y: 1 }, Position { x: 1, y: 3 }), Position { x: 1, y: 2 }), // one vertical
+            ((Position { x: 2, y: 3 }, Position { x: 1, y: 1 }), Position { x: 2, y: 2 }), // one diagonal
+            ((Position { x: 3, y: 2 }, Position { x: 1, y: 1 }), Position { x: 2, y: 2 }), // one diagonal
         ];
 
         for ((position_a, position_b), target_position_b) in tests {
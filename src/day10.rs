@@ -1,37 +1,41 @@
 use std::cell::RefCell;
-use std::collections::VecDeque;
 use std::fmt::Display;
+use std::io::BufRead;
+use std::iter::Peekable;
 use std::rc::Rc;
 use std::str::FromStr;
 
 use anyhow::Context;
 
-const INPUT: &str = include_str!("../inputs/day10.input");
-
 fn main() -> anyhow::Result<()> {
-    let program = Program::from_str(INPUT)?;
+    let args = aoc_2022::cli::DayArgs::init()?;
     let mut communication_device = CommunicationDevice::default();
-    communication_device.set_default_program(program);
 
     // PART 1 - 1 hour 39 minutes 43 seconds
-    communication_device.reset();
-    let part_1_solution = communication_device
-        .calculate_sum_of_interesting_signal_strengths(1, vec![20, 60, 100, 140, 180, 220])
-        .context("while calculating sum of interesting signal strengths")?;
+    let part_1_solution = tracing::info_span!("part", number = 1).in_scope(|| {
+        communication_device
+            .calculate_sum_of_interesting_signal_strengths(
+                Program::read(args.input_reader(2022, "day10")?),
+                1,
+                vec![20, 60, 100, 140, 180, 220],
+            )
+            .context("while calculating sum of interesting signal strengths")
+    })?;
     println!("part_1_solution: {part_1_solution}");
 
     // PART 2 - 4 minutes 4 seconds + 50 minutes 37 seconds = 54 minutes 41 seconds
-    communication_device.reset();
-    let part_2_solution = communication_device
-        .calculate_crt_image(1)
-        .context("while calculating crt image")?;
+    let part_2_solution = tracing::info_span!("part", number = 2).in_scope(|| {
+        communication_device
+            .calculate_crt_image(Program::read(args.input_reader(2022, "day10")?), 1)
+            .context("while calculating crt image")
+    })?;
     println!("part_2_solution:\n{part_2_solution}");
+    args.render("crt", || part_2_solution.clone())?;
 
     Ok(())
 }
 
 struct CommunicationDevice<const CRT_COLUMNS: usize = 40, const CRT_ROWS: usize = 6> {
-    default_program: Option<Program>,
     clock_circuit: ClockCircuit,
     crt: Rc<RefCell<Crt<CRT_COLUMNS, CRT_ROWS>>>,
 }
@@ -39,16 +43,11 @@ struct CommunicationDevice<const CRT_COLUMNS: usize = 40, const CRT_ROWS: usize
 impl<const CRT_COLUMNS: usize, const CRT_ROWS: usize> CommunicationDevice<CRT_COLUMNS, CRT_ROWS> {
     fn new() -> Self {
         Self {
-            default_program: None,
             clock_circuit: ClockCircuit::new(Cpu::new_with_register_x_value(0)),
             crt: Rc::new(RefCell::new(Crt::default())),
         }
     }
 
-    fn set_default_program(&mut self, program: Program) {
-        self.default_program = Some(program);
-    }
-
     fn reset(&mut self) {
         self.clock_circuit.reset();
         RefCell::borrow_mut(&self.crt).reset();
@@ -56,13 +55,12 @@ impl<const CRT_COLUMNS: usize, const CRT_ROWS: usize> CommunicationDevice<CRT_CO
 
     fn calculate_sum_of_interesting_signal_strengths(
         &mut self,
+        program: Program,
         starting_cpu_x_register_value: i64,
         look_during_cycles: Vec<u128>,
     ) -> anyhow::Result<i64> {
         self.reset();
-        if let Some(ref program) = self.default_program {
-            self.clock_circuit.cpu.load(program.clone());
-        }
+        self.clock_circuit.cpu.load(program);
         self.clock_circuit.cpu.x_register.value = starting_cpu_x_register_value;
 
         let signal_strength_sums: Rc<RefCell<i64>> = Rc::new(RefCell::new(0));
@@ -88,12 +86,11 @@ impl<const CRT_COLUMNS: usize, const CRT_ROWS: usize> CommunicationDevice<CRT_CO
 
     fn calculate_crt_image(
         &mut self,
+        program: Program,
         starting_cpu_x_register_value: i64,
     ) -> anyhow::Result<String> {
         self.reset();
-        if let Some(ref program) = self.default_program {
-            self.clock_circuit.cpu.load(program.clone());
-        }
+        self.clock_circuit.cpu.load(program);
         self.clock_circuit.cpu.x_register.value = starting_cpu_x_register_value;
 
         let crt_clone = Rc::clone(&self.crt);
@@ -118,24 +115,54 @@ impl Default for CommunicationDevice {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+type Instructions = Box<dyn Iterator<Item = anyhow::Result<Instruction>>>;
+
+/// Instructions fetched one at a time, so that a program read from a stream is never in memory as
+/// a whole.
 struct Program {
-    instructions: VecDeque<Instruction>,
+    instructions: Peekable<Instructions>,
+}
+
+impl Program {
+    fn read(reader: impl BufRead + 'static) -> Self {
+        Self {
+            instructions: (Box::new(aoc_2022::stream::lines(reader).enumerate().map(
+                |(index, line)| {
+                    Instruction::from_str(&line?).with_context(|| format!("in line #{index}"))
+                },
+            )) as Instructions)
+                .peekable(),
+        }
+    }
+}
+
+impl From<Vec<Instruction>> for Program {
+    fn from(instructions: Vec<Instruction>) -> Self {
+        Self {
+            instructions: (Box::new(instructions.into_iter().map(Ok)) as Instructions).peekable(),
+        }
+    }
 }
 
 impl FromStr for Program {
     type Err = anyhow::Error;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Program {
-            instructions: s
-                .lines()
+        Ok(Self::from(
+            s.lines()
                 .enumerate()
                 .map(|(index, line)| {
                     Instruction::from_str(line).with_context(|| format!("in line #{index}"))
                 })
-                .collect::<Result<VecDeque<_>, _>>()?,
-        })
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+}
+
+impl std::fmt::Debug for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Program").finish_non_exhaustive()
     }
 }
 
@@ -166,7 +193,7 @@ impl FromStr for Instruction {
 struct Cpu {
     x_register: Register,
     instruction_register: Option<(Instruction, u128)>,
-    loaded_instructions: Option<VecDeque<Instruction>>,
+    loaded_program: Option<Program>,
 }
 
 impl Cpu {
@@ -174,18 +201,18 @@ impl Cpu {
         Self {
             x_register: Register::new(value),
             instruction_register: None,
-            loaded_instructions: None,
+            loaded_program: None,
         }
     }
 
     fn reset(&mut self) {
         self.x_register.value = 0;
         self.instruction_register = None;
-        self.loaded_instructions = None;
+        self.loaded_program = None;
     }
 
     fn load(&mut self, program: Program) {
-        self.loaded_instructions = Some(program.instructions);
+        self.loaded_program = Some(program);
     }
 
     fn calculate_needed_cycles(instruction: Instruction) -> u128 {
@@ -195,23 +222,25 @@ impl Cpu {
         }
     }
 
-    fn has_instruction_left(&self) -> bool {
+    fn has_instruction_left(&mut self) -> bool {
         self.instruction_register.is_some()
             || self
-                .loaded_instructions
-                .as_ref()
-                .map_or(false, |loaded_instructions| !loaded_instructions.is_empty())
+                .loaded_program
+                .as_mut()
+                .is_some_and(|loaded_program| loaded_program.instructions.peek().is_some())
     }
 
     fn tick(&mut self) -> Result<(), CPUTickError> {
         let (current_instruction, remaining_cycles) = match self.instruction_register.take() {
             None => {
                 let next_instruction = self
-                    .loaded_instructions
+                    .loaded_program
                     .as_mut()
                     .ok_or(CPUTickError::NoProgramLoaded)?
-                    .pop_front()
-                    .ok_or(CPUTickError::ProgramEnded)?;
+                    .instructions
+                    .next()
+                    .ok_or(CPUTickError::ProgramEnded)?
+                    .map_err(CPUTickError::Fetch)?;
                 let needed_cycles = Self::calculate_needed_cycles(next_instruction);
                 (next_instruction, needed_cycles)
             }
@@ -228,16 +257,21 @@ impl Cpu {
             Ok(())
         } else {
             match current_instruction {
-                Instruction::NoOp => Ok(()),
+                Instruction::NoOp => {}
                 Instruction::AddX(value) => {
                     self.x_register.value = self
                         .x_register
                         .value
                         .checked_add(i64::from(value))
                         .ok_or(CPUTickError::Overflow)?;
-                    Ok(())
                 }
             }
+            tracing::trace!(
+                instruction = ?current_instruction,
+                x_register = self.x_register.value,
+                "instruction retired"
+            );
+            Ok(())
         }
     }
 }
@@ -248,6 +282,8 @@ enum CPUTickError {
     NoProgramLoaded,
     #[error("Program ended.")]
     ProgramEnded,
+    #[error("Could not fetch the next instruction ({0:#}).")]
+    Fetch(anyhow::Error),
     #[error("Overflow happened.")]
     Overflow,
     #[error("An internal error occurred ({0}).")]
@@ -440,171 +476,26 @@ impl Display for Pixel {
 #[cfg(test)]
 mod tests {
     use super::*;
+
     use std::cell::RefCell;
+    use std::io::Cursor;
     use std::rc::Rc;
 
-    const TEST_INPUT: &str = "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop";
-
-    #[test]
-    fn test_part_1_default() -> anyhow::Result<()> {
-        // Arrange
-        let program = Program::from_str(TEST_INPUT)?;
-        let mut communication_device = CommunicationDevice::default();
-        communication_device.set_default_program(program);
-
-        // Act
-        let signal_strength_sums = communication_device
-            .calculate_sum_of_interesting_signal_strengths(1, vec![20, 60, 100, 140, 180, 220])?;
-
-        // Assert
-        assert_eq!(signal_strength_sums, 13_140);
-
-        Ok(())
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day10"],
+        test_part_1_default: "part_1_solution" => |input| {
+            let mut communication_device = CommunicationDevice::default();
+            communication_device.calculate_sum_of_interesting_signal_strengths(
+                Program::read(Cursor::new(input.to_owned())),
+                1,
+                vec![20, 60, 100, 140, 180, 220],
+            )
+        },
+        test_part_2_default: "part_2_solution" => |input| {
+            let mut communication_device = CommunicationDevice::default();
+            communication_device.calculate_crt_image(Program::read(Cursor::new(input.to_owned())), 1)
+        },
     }
 
     #[test]
@@ -619,7 +510,7 @@ addx -5";
 
         // Assert
         assert_eq!(
-            program.instructions,
+            program.instructions.collect::<anyhow::Result<Vec<_>>>()?,
             vec![
                 Instruction::NoOp,
                 Instruction::AddX(3),
@@ -635,13 +526,11 @@ addx -5";
         let mut cpu = Cpu::new_with_register_x_value(1);
         assert_eq!(cpu.x_register.value, 1);
 
-        cpu.load(Program {
-            instructions: VecDeque::from(vec![
-                Instruction::NoOp,
-                Instruction::AddX(3),
-                Instruction::AddX(-5),
-            ]),
-        });
+        cpu.load(Program::from(vec![
+            Instruction::NoOp,
+            Instruction::AddX(3),
+            Instruction::AddX(-5),
+        ]));
 
         cpu.tick()?;
         assert_eq!(cpu.x_register.value, 1);
@@ -666,9 +555,7 @@ addx -5";
         let mut new_cpu = Cpu::new_with_register_x_value(1);
         assert_eq!(new_cpu.x_register.value, 1);
 
-        new_cpu.load(Program {
-            instructions: VecDeque::from(vec![Instruction::NoOp, Instruction::AddX(3)]),
-        });
+        new_cpu.load(Program::from(vec![Instruction::NoOp, Instruction::AddX(3)]));
 
         let cycle_history = Rc::new(RefCell::new(Vec::new()));
         let cycle_history_cloned = Rc::clone(&cycle_history);
@@ -682,15 +569,15 @@ addx -5";
             Ok(())
         });
         assert_eq!(clock_circuit.cycles_completed, 0);
-        assert_eq!(*RefCell::borrow(&cycle_history), vec![]);
+        assert_eq!(*RefCell::borrow(&cycle_history), Vec::<i64>::new());
 
         clock_circuit.run()?;
         assert_eq!(clock_circuit.cycles_completed, 3);
         assert_eq!(*RefCell::borrow(&cycle_history), vec![1, 1, 4]);
 
-        clock_circuit.cpu.load(Program {
-            instructions: VecDeque::from(vec![Instruction::AddX(-5)]),
-        });
+        clock_circuit
+            .cpu
+            .load(Program::from(vec![Instruction::AddX(-5)]));
 
         clock_circuit.run()?;
         assert_eq!(clock_circuit.cycles_completed, 5);
@@ -698,28 +585,4 @@ addx -5";
 
         Ok(())
     }
-
-    #[test]
-    fn part_2_default() -> anyhow::Result<()> {
-        // Arrange
-        let program = Program::from_str(TEST_INPUT)?;
-        let mut communication_device = CommunicationDevice::default();
-        communication_device.set_default_program(program);
-
-        // Act
-        let produced_image = communication_device.calculate_crt_image(1)?;
-
-        // Assert
-        assert_eq!(
-            produced_image,
-            "##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######....."
-        );
-
-        Ok(())
-    }
 }
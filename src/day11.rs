@@ -1,28 +1,35 @@
 use anyhow::Context;
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("../inputs/day11.input");
+use aoc_2022::parse::{self, Cursor, ParseError, ParseResult};
 
 fn main() -> anyhow::Result<()> {
+    let args = aoc_2022::cli::DayArgs::init()?;
+    let input = args.input(2022, "day11")?;
+
     // PART 1 - 1 hour 16 minutes 53 seconds
-    let mut monkey_keep_away_part_1 = MonkeyKeepAway::from_str(INPUT)?;
-    monkey_keep_away_part_1
-        .run_for_rounds(20, WorryType::WithRelief)
-        .context("Failed running for 20 rounds in part 1.")?;
-    let part_1_solution = monkey_keep_away_part_1
-        .calculate_level_of_monkey_business()
-        .context("Failed calculating monkey business in part 2.")?;
+    let part_1_solution = tracing::info_span!("part", number = 1).in_scope(|| {
+        let mut monkey_keep_away_part_1 = MonkeyKeepAway::from_str(&input)?;
+        monkey_keep_away_part_1
+            .run_for_rounds(20, WorryType::WithRelief)
+            .context("Failed running for 20 rounds in part 1.")?;
+        monkey_keep_away_part_1
+            .calculate_level_of_monkey_business()
+            .context("Failed calculating monkey business in part 2.")
+    })?;
     println!("part_1_solution: {part_1_solution}");
 
     // PART 2 - 1 hour 56 minutes 4 seconds + 2 hours 24 minutes 26 seconds + 27 minutes 29 seconds = 4 hours 47 minutes 59 seconds
     // third attempt with the help of https://github.com/schubart/AdventOfCode_2022_Rust/blob/c05c1f267566df54a94cf5364f6cbc5258756810/day11/src/lib.rs
-    let mut monkey_keep_away_part_2 = MonkeyKeepAway::from_str(INPUT)?;
-    monkey_keep_away_part_2
-        .run_for_rounds(10_000, WorryType::NoRelief)
-        .context("Failed running for 10_000 rounds in part 2.")?;
-    let part_2_solution = monkey_keep_away_part_2
-        .calculate_level_of_monkey_business()
-        .context("Failed calculating monkey business in part 2.")?;
+    let part_2_solution = tracing::info_span!("part", number = 2).in_scope(|| {
+        let mut monkey_keep_away_part_2 = MonkeyKeepAway::from_str(&input)?;
+        monkey_keep_away_part_2
+            .run_for_rounds(10_000, WorryType::NoRelief)
+            .context("Failed running for 10_000 rounds in part 2.")?;
+        monkey_keep_away_part_2
+            .calculate_level_of_monkey_business()
+            .context("Failed calculating monkey business in part 2.")
+    })?;
     println!("level_of_monkey_business: {part_2_solution}");
 
     Ok(())
@@ -94,6 +101,12 @@ impl MonkeyKeepAway {
                 } else {
                     test.target_if_false.0
                 };
+                tracing::trace!(
+                    from = current_monkey_index,
+                    to = target,
+                    worry_level = item.worry_level.0,
+                    "monkey throws item"
+                );
                 self.monkeys
                     .get_mut(target)
                     .ok_or_else(|| anyhow::anyhow!("Could not find monkey #{target} to which monkey #{current_monkey_index} throws something."))?
@@ -111,6 +124,7 @@ impl MonkeyKeepAway {
         worry_type: WorryType,
     ) -> anyhow::Result<()> {
         for round in 0..count_of_rounds {
+            tracing::debug!(round, "running round");
             self.run(worry_type)
                 .with_context(|| format!("Run #{round} failed."))?;
         }
@@ -123,9 +137,9 @@ impl MonkeyKeepAway {
             .iter()
             .map(|monkey| (monkey.index, monkey.count_of_item_inspections))
             .collect::<Vec<_>>();
-        monkeys_with_their_inspection_counts.sort_by(|&(_, ref a), &(_, ref b)| b.cmp(a));
+        monkeys_with_their_inspection_counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
         let most_inspection_count = monkeys_with_their_inspection_counts
-            .get(0)
+            .first()
             .ok_or_else(|| {
                 anyhow::anyhow!(
                     "There might be no monkeys, because cannot get first monkey inspection count."
@@ -141,81 +155,22 @@ impl MonkeyKeepAway {
 }
 
 impl FromStr for MonkeyKeepAway {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self {
-            monkeys: s
-                .split("\n\n")
-                .enumerate()
-                .map(|(part_index, monkey_string)| {
-                    let monkey_lines: [&str; 6] = monkey_string
-                        .lines()
-                        .collect::<Vec<_>>()
-                        .try_into()
-                        .map_err(|vec: Vec<_>| {
-                            anyhow::anyhow!(
-                                "Part #{part_index} does not have exactly 6 but has {} lines.",
-                                vec.len()
-                            )
-                        })?;
-
-                    let monkey_index: usize = monkey_lines[0]
-                        .strip_prefix("Monkey ")
-                        .ok_or_else(|| anyhow::anyhow!("Part #{part_index} first line does not start with \"Monkey \"."))?
-                        .strip_suffix(':')
-                        .ok_or_else(|| anyhow::anyhow!("Part #{part_index} first line does not end with \":\"."))?
-                        .parse()
-                        .with_context(|| format!("Part #{part_index} monkey index is no valid number."))?;
-
-                    let items = monkey_lines[1]
-                        .strip_prefix("  Starting items: ")
-                        .ok_or_else(|| anyhow::anyhow!("Part #{part_index} second line does not start with \"  Starting items: \"."))?
-                        .split(", ")
-                        .map(|starting_item_worry_level_str| {
-                            starting_item_worry_level_str
-                                .parse()
-                                .map(|worry_level| Item {
-                                    worry_level: WorryLevel(worry_level),
-                                })
-                        })
-                        .collect::<Result<Vec<_>, _>>()
-                        .with_context(|| format!("Part #{part_index} starting items contain an invalid number."))?;
-
-                    let operation_str = monkey_lines[2].strip_prefix("  Operation: ")
-                        .ok_or_else(|| anyhow::anyhow!("Part #{part_index} third line does not start with \"  Operation: \"."))?;
-                    let operation = Operation::from_str(operation_str)
-                        .with_context(|| format!("Part #{part_index} operation could not be parsed."))?;
-
-                    let condition_divisible_by = monkey_lines[3].strip_prefix("  Test: divisible by ")
-                        .ok_or_else(|| anyhow::anyhow!("Part #{part_index} fourth line does not start with \"  Test: divisible by \"."))?
-                        .parse()
-                        .with_context(|| format!("Part #{part_index} divisible by test number could not be parsed."))?;
-
-                    let test_if_true = monkey_lines[4]
-                        .strip_prefix("    If true: throw to monkey ")
-                        .ok_or_else(|| anyhow::anyhow!("Part #{part_index} fifth line does not start with \"    If true: throw to monkey \"."))?
-                        .parse()
-                        .with_context(|| format!("Part #{part_index} test if true target monkey index could not be parsed"))?;
-                    let test_if_false = monkey_lines[5]
-                        .strip_prefix("    If false: throw to monkey ")
-                        .ok_or_else(|| anyhow::anyhow!("Part #{part_index} fifth line does not start with \"    If false: throw to monkey \"."))?
-                        .parse()
-                        .with_context(|| format!("Part #{part_index} test if false target monkey index could not be parsed"))?;
-
-                    Ok(Monkey {
-                        index: MonkeyIndex(monkey_index),
-                        items,
-                        operation,
-                        test: Test {
-                            condition_divisible_by,
-                            target_if_true: MonkeyIndex(test_if_true),
-                            target_if_false: MonkeyIndex(test_if_false),
-                        },
-                        count_of_item_inspections: 0,
+        parse::all(s, |cursor| {
+            Ok(Self {
+                monkeys: cursor
+                    .blocks()
+                    .into_iter()
+                    .map(|mut block| {
+                        let monkey = Monkey::parse(&mut block)?;
+                        block.finish()?;
+                        Ok(monkey)
                     })
-                })
-                .collect::<Result<Vec<_>, anyhow::Error>>()?,
+                    .collect::<ParseResult<Vec<_>>>()?,
+            })
         })
     }
 }
@@ -235,6 +190,38 @@ struct Monkey {
     count_of_item_inspections: u128,
 }
 
+impl Monkey {
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        cursor.tag("Monkey ")?;
+        let index = MonkeyIndex(cursor.number()?);
+        cursor.tag(":\n  Starting items: ")?;
+        let items = cursor.separated(", ", |cursor| {
+            Ok(Item {
+                worry_level: WorryLevel(cursor.number()?),
+            })
+        })?;
+        cursor.tag("\n  Operation: ")?;
+        let operation = Operation::parse(cursor)?;
+        cursor.tag("\n  Test: divisible by ")?;
+        let condition_divisible_by = cursor.number()?;
+        cursor.tag("\n    If true: throw to monkey ")?;
+        let target_if_true = MonkeyIndex(cursor.number()?);
+        cursor.tag("\n    If false: throw to monkey ")?;
+        let target_if_false = MonkeyIndex(cursor.number()?);
+        Ok(Self {
+            index,
+            items,
+            operation,
+            test: Test {
+                condition_divisible_by,
+                target_if_true,
+                target_if_false,
+            },
+            count_of_item_inspections: 0,
+        })
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct MonkeyIndex(usize);
 
@@ -266,42 +253,20 @@ impl Operation {
     }
 }
 
-impl FromStr for Operation {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let suffix = s
-            .strip_prefix("new = old ")
-            .ok_or_else(|| anyhow::anyhow!("Value does not start with required \"new = old \"."))?;
-
-        let parts: [&str; 2] =
-            suffix
-                .split(' ')
-                .collect::<Vec<_>>()
-                .try_into()
-                .map_err(|vec: Vec<_>| {
-                    anyhow::anyhow!(
-                        "Value suffix after \"new = old \" are not two elements, but {} ({:?}).",
-                        vec.len(),
-                        vec
-                    )
-                })?;
-
-        match (parts[0], parts[1]) {
-            ("*", "old") => Ok(Self::ProductByFactorOld),
-            ("*", _) => Ok(Self::Product {
-                factor: parts[1]
-                    .parse()
-                    .context("Could not parse product factor.")?,
-            }),
-            ("+", _) => Ok(Self::Sum {
-                summand: parts[1].parse().context("Could not parse sum summand.")?,
-            }),
-            _ => Err(anyhow::anyhow!(
-                "Unexpected operator ({}) and operand ({}).",
-                parts[0],
-                parts[1]
-            )),
+impl Operation {
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        cursor.tag("new = old ")?;
+        if cursor.try_tag("* old") {
+            Ok(Self::ProductByFactorOld)
+        } else if cursor.try_tag("* ") {
+            Ok(Self::Product {
+                factor: cursor.number()?,
+            })
+        } else {
+            cursor.tag("+ ")?;
+            Ok(Self::Sum {
+                summand: cursor.number()?,
+            })
         }
     }
 }
@@ -318,49 +283,23 @@ struct Test {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
-
-    #[test]
-    fn test_part_1_default() -> anyhow::Result<()> {
-        // Arrange
-        let mut monkey_keep_away = MonkeyKeepAway::from_str(TEST_INPUT)?;
-
-        // Act
-        monkey_keep_away.run_for_rounds(20, WorryType::WithRelief)?;
-        let level_of_monkey_business = monkey_keep_away.calculate_level_of_monkey_business()?;
-
-        // Assert
-        assert_eq!(level_of_monkey_business, 10605);
-
-        Ok(())
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day11"],
+        test_part_1_default: "part_1_solution" => |input| {
+            let mut monkey_keep_away = MonkeyKeepAway::from_str(input)?;
+            monkey_keep_away.run_for_rounds(20, WorryType::WithRelief)?;
+            monkey_keep_away.calculate_level_of_monkey_business()
+        },
+        test_part_2_default: "level_of_monkey_business" => |input| {
+            let mut monkey_keep_away = MonkeyKeepAway::from_str(input)?;
+            monkey_keep_away.run_for_rounds(10_000, WorryType::NoRelief)?;
+            monkey_keep_away.calculate_level_of_monkey_business()
+        },
     }
 
+    const TEST_INPUT: &str = include_str!("../samples/2022/day11.input");
+
     #[test]
     fn test_monkey_keep_away_from_str() -> anyhow::Result<()> {
         // Act
@@ -495,19 +434,4 @@ Monkey 3:
 
         Ok(())
     }
-
-    #[test]
-    fn test_part_3_default() -> anyhow::Result<()> {
-        // Arrange
-        let mut monkey_keep_away = MonkeyKeepAway::from_str(TEST_INPUT)?;
-
-        // Act
-        monkey_keep_away.run_for_rounds(10_000, WorryType::NoRelief)?;
-        let level_of_monkey_business = monkey_keep_away.calculate_level_of_monkey_business()?;
-
-        // Assert
-        assert_eq!(level_of_monkey_business, 2_713_310_158);
-
-        Ok(())
-    }
 }
@@ -12,21 +12,45 @@ use std::str::FromStr;
 use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
 
-const INPUT: &str = include_str!("../inputs/day12.input");
+use aoc_2022::grid::Vector;
 
 fn main() {
-    let part_1_solution = calculate_minimal_path_length(INPUT);
+    let args = aoc_2022::cli::DayArgs::init().unwrap();
+    let input = args.input(2022, "day12").unwrap();
+
+    let shortest_path =
+        tracing::info_span!("part", number = 1).in_scope(|| find_shortest_path(&input));
+    let part_1_solution = shortest_path.len() as u64 - 1;
     println!("part_1_solution: {part_1_solution:?}");
+    args.render("path", || render_path(&input, &shortest_path))
+        .unwrap();
 
-    let part_2_solution = calculate_fewest_steps_required(INPUT);
+    let part_2_solution = tracing::info_span!("part", number = 2)
+        .in_scope(|| calculate_fewest_steps_required(&input));
     println!("part_2_solution: {part_2_solution:?}");
 }
 
-fn calculate_minimal_path_length(input: &str) -> u64 {
+fn find_shortest_path(input: &str) -> Vec<Position> {
     let graph = Graph::from_str(input).unwrap();
     let predecessors = dijkstra(&graph, graph.start);
-    let shortest_path = calculate_shortest_path(graph.end, &predecessors);
-    shortest_path.len() as u64 - 1
+    calculate_shortest_path(graph.end, &predecessors)
+}
+
+/// The heightmap with the path drawn in upper case letters.
+fn render_path(input: &str, path: &[Position]) -> String {
+    let mut lines = input
+        .lines()
+        .map(|line| line.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for position in path {
+        let cell = &mut lines[position.y][position.x];
+        *cell = cell.to_ascii_uppercase();
+    }
+    lines
+        .into_iter()
+        .map(|line| line.into_iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 fn calculate_fewest_steps_required(input: &str) -> u64 {
@@ -42,7 +66,7 @@ fn calculate_fewest_steps_required(input: &str) -> u64 {
                 .map(move |(column, _)| Position { x: column, y: row })
         })
         .collect::<Vec<_>>();
-    println!("Have {} possible starts...", possible_starts.len());
+    tracing::info!(count = possible_starts.len(), "found possible starts");
     let m = possible_starts
         .into_par_iter()
         .map(|start| {
@@ -63,6 +87,7 @@ struct Graph {
 impl FromStr for Graph {
     type Err = anyhow::Error;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut start = None;
         let mut end = None;
@@ -107,11 +132,7 @@ struct Vertex {
     elevation: u8,
 }
 
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-struct Position {
-    x: usize,
-    y: usize,
-}
+type Position = aoc_2022::grid::Position<usize>;
 
 fn dijkstra(graph: &Graph, start_vertex: Position) -> HashMap<Position, Option<Position>> {
     let mut distance = HashMap::new();
@@ -137,19 +158,9 @@ fn dijkstra(graph: &Graph, start_vertex: Position) -> HashMap<Position, Option<P
 
         let current_vertex = queue.remove(current_vertex_index);
 
-        let neighbours = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+        let neighbours = Vector::ORTHOGONAL
             .iter()
-            .filter_map(|(x_diff, y_diff)| {
-                current_vertex
-                    .x
-                    .checked_add_signed(*x_diff)
-                    .and_then(|new_x| {
-                        current_vertex
-                            .y
-                            .checked_add_signed(*y_diff)
-                            .and_then(|new_y| Some(Position { x: new_x, y: new_y }))
-                    })
-            })
+            .filter_map(|&direction| current_vertex.checked_add(direction))
             .filter(|pos| pos.y < graph.inner.len() && pos.x < graph.inner[pos.y].len())
             .filter(|neighbour_vertex| {
                 let cur = &graph.inner[current_vertex.y][current_vertex.x];
@@ -224,27 +235,10 @@ fn calculate_shortest_path(
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi";
-
-    #[test]
-    fn test_part_1_default() {
-        // Act
-        let minimal_path_length = calculate_minimal_path_length(TEST_INPUT);
-
-        // Assert
-        assert_eq!(minimal_path_length, 31);
-    }
-
-    #[test]
-    fn test_part_2_default() {
-        // Act
-        let minimal_path_length = calculate_fewest_steps_required(TEST_INPUT);
-
-        // Assert
-        assert_eq!(minimal_path_length, 29);
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day12"],
+        test_part_1_default: "part_1_solution" => |input| Ok(find_shortest_path(input).len() as u64 - 1),
+        test_part_2_default: "part_2_solution" => |input| Ok(calculate_fewest_steps_required(input)),
     }
 }
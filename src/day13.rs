@@ -6,16 +6,21 @@ use anyhow::Context;
 
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../inputs/day13.input");
+use aoc_2022::parse::{self, Cursor, ParseError, ParseResult};
 
 fn main() -> anyhow::Result<()> {
+    let args = aoc_2022::cli::DayArgs::init()?;
+    let input = args.input(2022, "day13")?;
+
     // Part 1 - 2 hours 36 minutes 58 seconds
-    let part_1_solution = calculate_sum_of_indices_of_pairs_in_right_order(INPUT)
+    let part_1_solution = tracing::info_span!("part", number = 1)
+        .in_scope(|| calculate_sum_of_indices_of_pairs_in_right_order(&input))
         .context("Failed calculating part 1 solution.")?;
     println!("part_1_solution: {part_1_solution}");
 
     // Part 2 - 21 minutes 56 seconds
-    let part_2_solution = calculate_decoder_key_for_distress_signal(INPUT)
+    let part_2_solution = tracing::info_span!("part", number = 2)
+        .in_scope(|| calculate_decoder_key_for_distress_signal(&input))
         .context("Failed calculating part 2 solution.")?;
     println!("part_2_solution: {part_2_solution}");
 
@@ -29,7 +34,7 @@ fn calculate_sum_of_indices_of_pairs_in_right_order(input: &str) -> anyhow::Resu
             .0
             .into_iter()
             .enumerate()
-            .filter(|&(_, ref packet_pair): &(usize, PacketPair)| packet_pair.is_in_right_order())
+            .filter(|(_, packet_pair): &(usize, PacketPair)| packet_pair.is_in_right_order())
             .map(|(index, _): (usize, PacketPair)| {
                 index.checked_add(1).with_context(|| {
                     format!("Could not calculate position for packet index #{index}.")
@@ -95,18 +100,23 @@ impl PacketPairs {
 }
 
 impl FromStr for PacketPairs {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
+    #[tracing::instrument(name = "parse", skip_all)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(
-            s.split("\n\n")
-                .map(|packet_pair| {
-                    PacketPair::from_str(packet_pair).with_context(|| {
-                        format!("Failed parsing PacketPair from \"{packet_pair}\".")
+        parse::all(s, |cursor| {
+            Ok(Self(
+                cursor
+                    .blocks()
+                    .into_iter()
+                    .map(|mut block| {
+                        let packet_pair = PacketPair::parse(&mut block)?;
+                        block.finish()?;
+                        Ok(packet_pair)
                     })
-                })
-                .collect::<Result<Vec<_>, _>>()?,
-        ))
+                    .collect::<ParseResult<Vec<_>>>()?,
+            ))
+        })
     }
 }
 
@@ -146,13 +156,13 @@ fn order_of_two_packet_data(left: &PacketData, right: &PacketData) -> PacketData
                 Ordering::Equal => PacketDataOrder::Indecisive,
             }
         }
-        (&PacketData::Integer(left_integer), &PacketData::List(ref right_list)) => {
+        (&PacketData::Integer(left_integer), PacketData::List(right_list)) => {
             order_of_two_packet_data_slices(&[PacketData::Integer(left_integer)], right_list)
         }
-        (&PacketData::List(ref left_list), &PacketData::Integer(right_integer)) => {
+        (PacketData::List(left_list), &PacketData::Integer(right_integer)) => {
             order_of_two_packet_data_slices(left_list, &[PacketData::Integer(right_integer)])
         }
-        (&PacketData::List(ref left_list), &PacketData::List(ref right_list)) => {
+        (PacketData::List(left_list), PacketData::List(right_list)) => {
             order_of_two_packet_data_slices(left_list, right_list)
         }
     }
@@ -167,24 +177,11 @@ impl PacketPair {
     }
 }
 
-impl FromStr for PacketPair {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let [left, right]: [Packet; 2] = s
-            .lines()
-            .map(|line| {
-                Packet::from_str(line)
-                    .with_context(|| format!("Could not parse Packet from string \"{line}\"."))
-            })
-            .collect::<Result<Vec<_>, _>>()?
-            .try_into()
-            .map_err(|vec: Vec<_>| {
-                anyhow::anyhow!(
-                    "Could not transform Vec<_> into [_; 2], because it has {} elements.",
-                    vec.len()
-                )
-            })?;
+impl PacketPair {
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        let left = Packet::parse(cursor)?;
+        cursor.tag("\n")?;
+        let right = Packet::parse(cursor)?;
         Ok(Self { left, right })
     }
 }
@@ -192,16 +189,10 @@ impl FromStr for PacketPair {
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct Packet(Vec<PacketData>);
 
-impl FromStr for Packet {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let packet_data =
-            PacketData::from_str(s).context("Could not parse PacketData from string.")?;
-        match packet_data {
-            PacketData::List(packet_data_vec) => Ok(Self(packet_data_vec)),
-            PacketData::Integer(_) => Err(anyhow::anyhow!("Top-Level integer is not allowed.")),
-        }
+impl Packet {
+    /// A packet is always a list, never a top-level integer.
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        cursor.list("[", ",", "]", PacketData::parse).map(Self)
     }
 }
 
@@ -224,105 +215,12 @@ enum PacketData {
     Integer(u8),
 }
 
-impl FromStr for PacketData {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with('[') {
-            let mut opened_brackets: i64 = 0;
-            let mut optional_main_bracket_open_index: Option<usize> = None;
-            let mut optional_main_bracket_close_index: Option<usize> = None;
-            let mut indices_of_commas: Vec<usize> = Vec::new();
-            for (index, character) in s.chars().enumerate() {
-                match character {
-                    '[' => {
-                        if optional_main_bracket_open_index.is_none() {
-                            optional_main_bracket_open_index = Some(index);
-                        }
-                        opened_brackets = opened_brackets.checked_add(1)
-                            .with_context(|| format!("Could not increase opened_brackets during character '{character}' at index #{index}."))?;
-                    }
-                    ']' => {
-                        opened_brackets = opened_brackets.checked_sub(1)
-                            .with_context(|| format!("Could not decrease opened_brackets during character '{character}' at index #{index}."))?;
-                        if opened_brackets == 0 && optional_main_bracket_close_index.is_none() {
-                            optional_main_bracket_close_index = Some(index);
-                        }
-                    }
-                    ',' => {
-                        if opened_brackets == 1 {
-                            indices_of_commas.push(index);
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if opened_brackets != 0 {
-                return Err(anyhow::anyhow!(
-                    "Found uneven bracket number of {opened_brackets}."
-                ));
-            }
-            let main_bracket_open_index = optional_main_bracket_open_index
-                .context("Could not find main bracket open index.")?;
-            let main_bracket_close_index = optional_main_bracket_close_index
-                .context("Could not find main bracket close index.")?;
-            let skip_chars = main_bracket_open_index.checked_add(1).with_context(|| {
-                format!("Failed increasing {main_bracket_open_index} while calculating skip_chars.")
-            })?;
-            let take_chars = main_bracket_close_index
-                .checked_sub(main_bracket_open_index)
-                .ok_or_else(|| anyhow::anyhow!("Failed doing subtraction `{main_bracket_close_index} - {main_bracket_open_index}` while calculating take_chars."))?
-                .checked_sub(1)
-                .ok_or_else(|| anyhow::anyhow!("Failed decrementing result of `{main_bracket_close_index} - {main_bracket_open_index}` while calculating take_chars."))?;
-            let text_between_main_brackets = s
-                .chars()
-                .skip(skip_chars)
-                .take(take_chars)
-                .collect::<String>();
-
-            if text_between_main_brackets.is_empty() {
-                Ok(Self::List(Vec::new()))
-            } else {
-                let mut parts = Vec::new();
-                let mut last_offset = 0;
-                for comma in indices_of_commas {
-                    let parts_take_length = comma.checked_sub(last_offset)
-                        .ok_or_else(|| anyhow::anyhow!("Failed subtracting `{comma} - {last_offset}` while calculating parts_take_length for comma at {comma}."))?
-                        .checked_sub(1)
-                        .ok_or_else(|| anyhow::anyhow!("Failed decrementing result of `{comma} - {last_offset}` while calculating parts_take_length for comma at {comma}."))?;
-                    parts.push(
-                        text_between_main_brackets
-                            .chars()
-                            .skip(last_offset)
-                            .take(parts_take_length)
-                            .collect::<String>(),
-                    );
-                    last_offset = comma;
-                }
-                parts.push(
-                    text_between_main_brackets
-                        .chars()
-                        .skip(last_offset)
-                        .collect::<String>(),
-                );
-
-                Ok(Self::List(
-                    parts
-                        .into_iter()
-                        .map(|part| {
-                            Self::from_str(&part).with_context(|| {
-                                format!(
-                                    "Failed parsing PacketData::List part from part \"{part}\"."
-                                )
-                            })
-                        })
-                        .collect::<Result<Vec<_>, _>>()?,
-                ))
-            }
+impl PacketData {
+    fn parse(cursor: &mut Cursor<'_>) -> ParseResult<Self> {
+        if cursor.rest().starts_with('[') {
+            cursor.list("[", ",", "]", Self::parse).map(Self::List)
         } else {
-            Ok(Self::Integer(s.parse().with_context(|| {
-                format!("Failed parsing PacketData::Integer from \"{s}\".")
-            })?))
+            cursor.number().map(Self::Integer)
         }
     }
 }
@@ -350,49 +248,10 @@ impl Display for PacketData {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "[1,1,3,1,1]
-[1,1,5,1,1]
-
-[[1],[2,3,4]]
-[[1],4]
-
-[9]
-[[8,7,6]]
-
-[[4,4],4,4]
-[[4,4],4,4,4]
-
-[7,7,7,7]
-[7,7,7]
-
-[]
-[3]
-
-[[[]]]
-[[]]
-
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]";
-
-    #[test]
-    fn test_part_1_default() -> anyhow::Result<()> {
-        // Act
-        let result = calculate_sum_of_indices_of_pairs_in_right_order(TEST_INPUT)?;
-
-        // Assert
-        assert_eq!(result, 13);
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_part_2_default() -> anyhow::Result<()> {
-        // Act
-        let result = calculate_decoder_key_for_distress_signal(TEST_INPUT)?;
-
-        // Assert
-        assert_eq!(result, 140);
-
-        Ok(())
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day13"],
+        test_part_1_default: "part_1_solution" => calculate_sum_of_indices_of_pairs_in_right_order,
+        test_part_2_default: "part_2_solution" => calculate_decoder_key_for_distress_signal,
     }
 }
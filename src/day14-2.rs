@@ -1,20 +1,28 @@
 use itertools::Itertools;
 use std::fmt::Display;
 
-const INPUT: &str = include_str!("../inputs/day14.input");
+use aoc_2022::grid::{Position, Vector};
+
+/// Where a sand unit tries to fall in turn: down, down to the left and down to the right.
+const FALLS: [Vector; 3] = [Vector::DOWN, Vector { x: -1, y: 1 }, Vector { x: 1, y: 1 }];
 
 fn main() {
+    let args = aoc_2022::cli::DayArgs::init().unwrap();
+    let input = args.input(2022, "day14").unwrap();
+
     // Part 1
     let start = std::time::Instant::now();
-    let part_1_solution = part_1(INPUT);
+    let part_1_solution = tracing::info_span!("part", number = 1).in_scope(|| part_1(&input));
     let end = std::time::Instant::now();
-    println!("part_1_solution: {part_1_solution} ({:?})", end - start);
+    tracing::info!(elapsed = ?(end - start), "part 1 solved");
+    println!("part_1_solution: {part_1_solution}");
 
     // Part 2
     let start = std::time::Instant::now();
-    let part_2_solution = part_2(INPUT);
+    let part_2_solution = tracing::info_span!("part", number = 2).in_scope(|| part_2(&input));
     let end = std::time::Instant::now();
-    println!("part_2_solution: {part_2_solution} ({:?})", end - start);
+    tracing::info!(elapsed = ?(end - start), "part 2 solved");
+    println!("part_2_solution: {part_2_solution}");
 }
 
 fn part_1(input: &str) -> u64 {
@@ -26,11 +34,8 @@ fn part_2(input: &str) -> u64 {
 }
 
 fn part_n(input: &str, with_rock_bottom: bool) -> u64 {
-    let mut slice = VerticalCaveSlice::parse_rock_structure(
-        input,
-        Position2D { x: 500, y: 0 },
-        with_rock_bottom,
-    );
+    let mut slice =
+        VerticalCaveSlice::parse_rock_structure(input, Position { x: 500, y: 0 }, with_rock_bottom);
     loop {
         let sand_result = slice.tick();
         if matches!(
@@ -45,19 +50,20 @@ fn part_n(input: &str, with_rock_bottom: bool) -> u64 {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct VerticalCaveSlice {
-    sand_start: Position2D,
+    sand_start: Position,
     slice_boundaries: Boundaries,
     slice: Vec<Vec<Element>>,
-    active_sand: Option<Position2D>,
+    active_sand: Option<Position>,
 }
 
 impl VerticalCaveSlice {
+    #[tracing::instrument(name = "parse", skip_all)]
     fn parse_rock_structure(
         rock_structure: &str,
-        sand_start: Position2D,
+        sand_start: Position,
         with_rock_bottom: bool,
     ) -> Self {
-        let rock_structure: Vec<Vec<Position2D>> = rock_structure
+        let rock_structure: Vec<Vec<Position>> = rock_structure
             .lines()
             .map(|line| {
                 line.split(" -> ")
@@ -68,7 +74,7 @@ impl VerticalCaveSlice {
                             .collect::<Vec<_>>()
                             .try_into()
                             .unwrap();
-                        Position2D { x, y }
+                        Position { x, y }
                     })
                     .collect::<Vec<_>>()
             })
@@ -85,7 +91,7 @@ impl VerticalCaveSlice {
         }
 
         let slice_height = usize::try_from(boundaries.bottom - boundaries.top + 1).unwrap();
-        let slice_width = usize::try_from(1 + 2 * slice_height).unwrap();
+        let slice_width = 1 + 2 * slice_height;
         let slice = vec![vec![Element::Air; slice_width]; slice_height];
         let slice_boundaries = Boundaries {
             top: boundaries.top,
@@ -105,10 +111,9 @@ impl VerticalCaveSlice {
             rock_line
                 .iter()
                 .tuple_windows::<(_, _)>()
-                .into_iter()
                 .for_each(|(start_rock, end_rock)| {
                     start_rock
-                        .to(end_rock)
+                        .span(*end_rock)
                         .iter()
                         .for_each(|position| output.set_element(position, Element::Rock))
                 })
@@ -117,7 +122,7 @@ impl VerticalCaveSlice {
         if with_rock_bottom {
             for x in output.slice_boundaries.left..=output.slice_boundaries.right {
                 output.set_element(
-                    &Position2D {
+                    &Position {
                         x,
                         y: output.slice_boundaries.bottom,
                     },
@@ -126,29 +131,32 @@ impl VerticalCaveSlice {
             }
         }
 
-        return output;
+        output
     }
 
     fn tick(&mut self) -> SandResult {
-        let is_blocked = |position: &Position2D| {
+        let is_blocked = |position: &Position| {
             matches!(self.get_element(position), Element::Sand | Element::Rock)
         };
         if let Some(active_sand) = self.active_sand {
+            let [down, down_left, down_right] =
+                FALLS.map(|fall| active_sand.checked_add(fall).unwrap());
             if active_sand.y + 1 > self.slice_boundaries.bottom {
                 self.active_sand = None;
                 SandResult::Vanished
-            } else if !is_blocked(&active_sand.down()) {
-                self.active_sand = Some(active_sand.down());
+            } else if !is_blocked(&down) {
+                self.active_sand = Some(down);
                 SandResult::Moved
-            } else if !is_blocked(&active_sand.down_left()) {
-                self.active_sand = Some(active_sand.down_left());
+            } else if !is_blocked(&down_left) {
+                self.active_sand = Some(down_left);
                 SandResult::Moved
-            } else if !is_blocked(&active_sand.down_right()) {
-                self.active_sand = Some(active_sand.down_right());
+            } else if !is_blocked(&down_right) {
+                self.active_sand = Some(down_right);
                 SandResult::Moved
             } else {
                 self.active_sand = None;
                 self.set_element(&active_sand, Element::Sand);
+                tracing::debug!(position = ?active_sand, "sand unit resting");
                 SandResult::Hardened
             }
         } else {
@@ -161,14 +169,14 @@ impl VerticalCaveSlice {
         }
     }
 
-    fn set_element(&mut self, position: &Position2D, element: Element) {
+    fn set_element(&mut self, position: &Position, element: Element) {
         let SliceIndices { y, x } = self.slice_boundaries.calculate_slice_indices(position);
         self.slice[y][x] = element;
     }
 
-    fn get_element(&self, position: &Position2D) -> &Element {
+    fn get_element(&self, position: &Position) -> &Element {
         let SliceIndices { y, x } = self.slice_boundaries.calculate_slice_indices(position);
-        return &self.slice[y][x];
+        &self.slice[y][x]
     }
 
     fn count_sand(&self) -> u64 {
@@ -212,41 +220,6 @@ enum Element {
     Sand,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-struct Position2D {
-    x: i64,
-    y: i64,
-}
-
-impl Position2D {
-    fn to(&self, other: &Self) -> Vec<Self> {
-        (self.x.min(other.x)..=self.x.max(other.x))
-            .flat_map(|x| (self.y.min(other.y)..=self.y.max(other.y)).map(move |y| Self { x, y }))
-            .collect()
-    }
-
-    fn down(&self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
-
-    fn down_left(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y + 1,
-        }
-    }
-
-    fn down_right(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y + 1,
-        }
-    }
-}
-
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct Boundaries {
     top: i64,
@@ -256,7 +229,7 @@ struct Boundaries {
 }
 
 impl Boundaries {
-    fn expand_to(&self, position: Position2D) -> Self {
+    fn expand_to(&self, position: Position) -> Self {
         Self {
             top: self.top.min(position.y),
             bottom: self.bottom.max(position.y),
@@ -265,15 +238,15 @@ impl Boundaries {
         }
     }
 
-    fn calculate_slice_indices(&self, position: &Position2D) -> SliceIndices {
+    fn calculate_slice_indices(&self, position: &Position) -> SliceIndices {
         let x = usize::try_from(position.x - self.left).unwrap();
         let y = usize::try_from(position.y - self.top).unwrap();
         SliceIndices { y, x }
     }
 }
 
-impl From<Position2D> for Boundaries {
-    fn from(value: Position2D) -> Self {
+impl From<Position> for Boundaries {
+    fn from(value: Position) -> Self {
         Self {
             top: value.y,
             bottom: value.y,
@@ -292,38 +265,26 @@ struct SliceIndices {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
-
-    #[test]
-    fn test_part_1_default() {
-        // Act
-        let part_1_solution = part_1(TEST_INPUT);
-
-        // Assert
-        assert_eq!(part_1_solution, 24);
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day14"],
+        test_part_1_default: "part_1_solution" => |input| Ok(part_1(input)),
+        test_part_2_default: "part_2_solution" => |input| Ok(part_2(input)),
     }
 
-    #[test]
-    fn test_part_2_default() {
-        // Act
-        let part_2_solution = part_2(TEST_INPUT);
-
-        // Assert
-        assert_eq!(part_2_solution, 93);
-    }
+    const TEST_INPUT: &str = include_str!("../samples/2022/day14.input");
 
     #[test]
     fn test_vertical_cave_slice_from_str() {
         // Act
         let vertical_cave_slice =
-            VerticalCaveSlice::parse_rock_structure(TEST_INPUT, Position2D { x: 500, y: 0 }, false);
+            VerticalCaveSlice::parse_rock_structure(TEST_INPUT, Position { x: 500, y: 0 }, false);
 
         // Assert
         use Element::{Air, Rock};
         #[rustfmt::skip]
         let target = VerticalCaveSlice {
-            sand_start: Position2D { x: 500, y: 0 },
+            sand_start: Position { x: 500, y: 0 },
             slice_boundaries: Boundaries {
                 top: 0,
                 bottom: 9,
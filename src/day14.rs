@@ -1,62 +1,69 @@
 use std::collections::HashSet;
 use std::fmt::Display;
-use std::io::Write;
 use std::str::FromStr;
 
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../inputs/day14.input");
+use aoc_2022::grid::{Position, Vector};
+
+/// Where a sand unit tries to fall in turn: down, down to the left and down to the right.
+const FALLS: [Vector; 3] = [Vector::DOWN, Vector { x: -1, y: 1 }, Vector { x: 1, y: 1 }];
 
 fn main() {
+    let args = aoc_2022::cli::DayArgs::init().unwrap();
+    let input = args.input(2022, "day14").unwrap();
+
     // Part 1
-    let part_1_solution = calculate_units_of_sand_staying_on_rocks(INPUT);
+    let cave = tracing::info_span!("part", number = 1).in_scope(|| fill_cave(&input));
+    let part_1_solution = cave.still_sand.len();
     println!("part_1_solution: {part_1_solution}");
+    args.render("cave", || cave.to_string()).unwrap();
 
     // Part 2
-    let part_2_solution = calculate_units_of_sand_staying_on_rocks_with_infinite_rock_bottom(INPUT);
+    let cave_with_floor = tracing::info_span!("part", number = 2)
+        .in_scope(|| fill_cave_with_infinite_rock_bottom(&input));
+    let part_2_solution = cave_with_floor.still_sand.len();
     println!("part_2_solution: {part_2_solution}");
+    args.render("cave-with-floor", || cave_with_floor.to_string())
+        .unwrap();
 }
 
-fn calculate_units_of_sand_staying_on_rocks(input: &str) -> u64 {
-    VerticalCaveSlice::parse_rock_scan(Position2D { x: 500, y: 0 }, input)
+fn fill_cave(input: &str) -> VerticalCaveSlice {
+    VerticalCaveSlice::parse_rock_scan(Position { x: 500, y: 0 }, input)
         .unwrap()
         .steps_till_full_of_sand()
-        .still_sand
-        .len() as u64
 }
 
-fn calculate_units_of_sand_staying_on_rocks_with_infinite_rock_bottom(input: &str) -> u64 {
-    let m = VerticalCaveSlice::parse_rock_scan(Position2D { x: 500, y: 0 }, input)
+fn fill_cave_with_infinite_rock_bottom(input: &str) -> VerticalCaveSlice {
+    let m = VerticalCaveSlice::parse_rock_scan(Position { x: 500, y: 0 }, input)
         .unwrap()
         .with_infinite_rock_bottom()
         .steps_till_full_of_sand();
-    std::fs::File::create("out")
-        .unwrap()
-        .write_all(m.to_string().as_bytes())
-        .unwrap();
-    m.still_sand.len() as u64
+    tracing::trace!("cave full of sand:{m}");
+    m
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct VerticalCaveSlice {
-    sand_start: Position2D,
-    rocks: HashSet<Position2D>,
-    moving_sand: Option<Position2D>,
-    still_sand: HashSet<Position2D>,
+    sand_start: Position,
+    rocks: HashSet<Position>,
+    moving_sand: Option<Position>,
+    still_sand: HashSet<Position>,
     infinite_rock_bottom_y: Option<i64>,
 }
 
 impl VerticalCaveSlice {
-    fn parse_rock_scan(sand_start: Position2D, rock_scan: &str) -> anyhow::Result<Self> {
+    #[tracing::instrument(name = "parse", skip_all)]
+    fn parse_rock_scan(sand_start: Position, rock_scan: &str) -> anyhow::Result<Self> {
         Ok(Self {
             sand_start,
             rocks: rock_scan
                 .lines()
                 .flat_map(|line| {
                     line.split(" -> ")
-                        .map(|corner| Position2D::from_str(corner).unwrap())
+                        .map(|corner| Position::from_str(corner).unwrap())
                         .tuple_windows::<(_, _)>()
-                        .flat_map(|(from, to)| from.to(&to))
+                        .flat_map(|(from, to)| from.span(to))
                 })
                 .unique()
                 .collect::<HashSet<_>>(),
@@ -84,12 +91,11 @@ impl VerticalCaveSlice {
 
             let mut to = from.clone();
             if let Some(previous_position) = to.moving_sand {
-                let down = previous_position.down();
-                let down_left = previous_position.down_left();
-                let down_right = previous_position.down_right();
+                let [down, down_left, down_right] =
+                    FALLS.map(|fall| previous_position.checked_add(fall).unwrap());
 
                 let is_blocked =
-                    |new: &Position2D| from.rocks.contains(new) || from.still_sand.contains(new);
+                    |new: &Position| from.rocks.contains(new) || from.still_sand.contains(new);
 
                 let (new_position, found_end) = if !is_blocked(&down) {
                     (down, false)
@@ -102,12 +108,17 @@ impl VerticalCaveSlice {
                 };
 
                 if found_end {
+                    tracing::debug!(position = ?new_position, "sand unit resting");
                     to.still_sand.insert(new_position);
                     to.moving_sand = None;
                 } else {
                     match &from.infinite_rock_bottom_y {
                         Some(infinite_rock_bottom_y) => {
                             if new_position.y >= *infinite_rock_bottom_y {
+                                tracing::debug!(
+                                    position = ?previous_position,
+                                    "sand unit resting on infinite rock bottom"
+                                );
                                 to.still_sand.insert(previous_position);
                                 to.moving_sand = None;
                             } else {
@@ -127,10 +138,10 @@ impl VerticalCaveSlice {
                 to.moving_sand = Some(to.sand_start);
             }
 
-            return to;
+            to
         }
 
-        return flow(self);
+        flow(self)
     }
 
     fn steps_till_sand_resting(&self) -> Self {
@@ -151,12 +162,8 @@ impl VerticalCaveSlice {
             }
 
             let created_new_sand = current.moving_sand.is_none() && next.moving_sand.is_some();
-            if created_new_sand {
-                if started_with_filled_sand_start {
-                    return current;
-                } else if steps > 1 {
-                    return current;
-                }
+            if created_new_sand && (started_with_filled_sand_start || steps > 1) {
+                return current;
             }
             current = next;
         }
@@ -164,13 +171,8 @@ impl VerticalCaveSlice {
 
     fn steps_till_full_of_sand(&self) -> Self {
         let mut current = self.clone();
-        let mut last = std::time::Instant::now();
         loop {
             let next = current.steps_till_sand_resting();
-            if last.elapsed().as_secs_f64() > 1f64 || next.still_sand.len() % 2000 == 0 {
-                println!("{} Sand", next.still_sand.len());
-            }
-            last = std::time::Instant::now();
             if current == next {
                 return next;
             }
@@ -198,7 +200,7 @@ impl Display for VerticalCaveSlice {
         } = boundaries;
 
         // --- HORIZONTAL AXIS NUMBERS ---
-        let mut horizontal_numbers_i64 = vec![left, 500, right];
+        let mut horizontal_numbers_i64 = [left, 500, right];
         horizontal_numbers_i64.sort();
         let mut horizontal_numbers = horizontal_numbers_i64
             .iter()
@@ -252,10 +254,10 @@ impl Display for VerticalCaveSlice {
             writeln!(f)?;
             write!(f, "{row:>width$} ", width = max_length_y_axis_digits)?;
             for column in left..=right {
-                if let Some(_) = self.infinite_rock_bottom_y.filter(|y| *y == row) {
+                if self.infinite_rock_bottom_y == Some(row) {
                     write!(f, "#")?;
                 } else {
-                    let position = Position2D { x: column, y: row };
+                    let position = Position { x: column, y: row };
                     if self.rocks.contains(&position) {
                         write!(f, "#")?;
                     } else if self.still_sand.contains(&position) {
@@ -273,55 +275,6 @@ impl Display for VerticalCaveSlice {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
-struct Position2D {
-    x: i64,
-    y: i64,
-}
-
-impl Position2D {
-    fn to(&self, other: &Self) -> Vec<Self> {
-        (self.x.min(other.x)..=self.x.max(other.x))
-            .flat_map(|x| (self.y.min(other.y)..=self.y.max(other.y)).map(move |y| Self { x, y }))
-            .collect::<Vec<_>>()
-    }
-
-    fn down(&self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
-
-    fn down_left(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y + 1,
-        }
-    }
-
-    fn down_right(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y + 1,
-        }
-    }
-}
-
-impl FromStr for Position2D {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let [x, y]: [i64; 2] = s
-            .split(',')
-            .map(|element| element.parse().unwrap())
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
-        Ok(Self { x, y })
-    }
-}
-
 struct Boundaries {
     top: i64,
     bottom: i64,
@@ -330,7 +283,7 @@ struct Boundaries {
 }
 
 impl Boundaries {
-    fn expand_by(&self, position: &Position2D) -> Self {
+    fn expand_by(&self, position: &Position) -> Self {
         Self {
             top: self.top.min(position.y),
             bottom: self.bottom.max(position.y),
@@ -339,7 +292,7 @@ impl Boundaries {
         }
     }
 
-    fn contains(&self, position: &Position2D) -> bool {
+    fn contains(&self, position: &Position) -> bool {
         position.x >= self.left
             && position.x <= self.right
             && position.y >= self.top
@@ -351,65 +304,56 @@ impl Boundaries {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
-
-    #[test]
-    fn test_part_1_default() {
-        // Act
-        let units_of_sand = calculate_units_of_sand_staying_on_rocks(TEST_INPUT);
-
-        // Assert
-        assert_eq!(units_of_sand, 24);
+    aoc_2022::sample_tests! {
+        year: 2022,
+        samples: ["day14"],
+        test_part_1_default: "part_1_solution" => |input| {
+            Ok(fill_cave(input).still_sand.len())
+        },
+        test_part_2_default: "part_2_solution" => |input| {
+            Ok(fill_cave_with_infinite_rock_bottom(input).still_sand.len())
+        },
     }
 
-    #[test]
-    fn test_part_2_default() {
-        // Act
-        let units_of_sand =
-            calculate_units_of_sand_staying_on_rocks_with_infinite_rock_bottom(TEST_INPUT);
-
-        // Assert
-        assert_eq!(units_of_sand, 93);
-    }
+    const TEST_INPUT: &str = include_str!("../samples/2022/day14.input");
 
     #[test]
     fn test_vertical_cave_slice_parse_rock_scan() {
         // Act
         let vertical_cave_slice =
-            VerticalCaveSlice::parse_rock_scan(Position2D { x: 500, y: 0 }, TEST_INPUT).unwrap();
+            VerticalCaveSlice::parse_rock_scan(Position { x: 500, y: 0 }, TEST_INPUT).unwrap();
 
         // Assert
         assert_eq!(
             vertical_cave_slice,
             VerticalCaveSlice {
-                sand_start: Position2D { x: 500, y: 0 },
+                sand_start: Position { x: 500, y: 0 },
                 rocks: HashSet::from([
                     // 498,4 -> 498,6
-                    Position2D { x: 498, y: 4 },
-                    Position2D { x: 498, y: 5 },
-                    Position2D { x: 498, y: 6 },
+                    Position { x: 498, y: 4 },
+                    Position { x: 498, y: 5 },
+                    Position { x: 498, y: 6 },
                     // (498,6) -> 496,6
-                    Position2D { x: 496, y: 6 },
-                    Position2D { x: 497, y: 6 },
+                    Position { x: 496, y: 6 },
+                    Position { x: 497, y: 6 },
                     // 503,4 -> 502,4
-                    Position2D { x: 502, y: 4 },
-                    Position2D { x: 503, y: 4 },
+                    Position { x: 502, y: 4 },
+                    Position { x: 503, y: 4 },
                     // (502,4) -> 502,9
-                    Position2D { x: 502, y: 5 },
-                    Position2D { x: 502, y: 6 },
-                    Position2D { x: 502, y: 7 },
-                    Position2D { x: 502, y: 8 },
-                    Position2D { x: 502, y: 9 },
+                    Position { x: 502, y: 5 },
+                    Position { x: 502, y: 6 },
+                    Position { x: 502, y: 7 },
+                    Position { x: 502, y: 8 },
+                    Position { x: 502, y: 9 },
                     // (502,9) -> 494,9
-                    Position2D { x: 494, y: 9 },
-                    Position2D { x: 495, y: 9 },
-                    Position2D { x: 496, y: 9 },
-                    Position2D { x: 497, y: 9 },
-                    Position2D { x: 498, y: 9 },
-                    Position2D { x: 499, y: 9 },
-                    Position2D { x: 500, y: 9 },
-                    Position2D { x: 501, y: 9 },
+                    Position { x: 494, y: 9 },
+                    Position { x: 495, y: 9 },
+                    Position { x: 496, y: 9 },
+                    Position { x: 497, y: 9 },
+                    Position { x: 498, y: 9 },
+                    Position { x: 499, y: 9 },
+                    Position { x: 500, y: 9 },
+                    Position { x: 501, y: 9 },
                 ]),
                 moving_sand: None,
                 still_sand: HashSet::new(),
@@ -422,7 +366,7 @@ mod tests {
     fn test_vertical_cave_slice_to_string() {
         // Arrange
         let vertical_cave_slice =
-            VerticalCaveSlice::parse_rock_scan(Position2D { x: 500, y: 0 }, TEST_INPUT).unwrap();
+            VerticalCaveSlice::parse_rock_scan(Position { x: 500, y: 0 }, TEST_INPUT).unwrap();
 
         // Act
         let vertical_cave_slice_string = vertical_cave_slice.to_string();
@@ -450,31 +394,31 @@ mod tests {
     #[test]
     fn test_position2d_to() {
         // Arrange
-        let from_a = Position2D { x: 498, y: 4 };
-        let to_a = Position2D { x: 498, y: 6 };
+        let from_a = Position { x: 498, y: 4 };
+        let to_a = Position { x: 498, y: 6 };
 
-        let from_b = Position2D { x: 498, y: 6 };
-        let to_b = Position2D { x: 496, y: 6 };
+        let from_b = Position { x: 498, y: 6 };
+        let to_b = Position { x: 496, y: 6 };
 
         // Act
-        let range_a = from_a.to(&to_a);
-        let range_b = from_b.to(&to_b);
+        let range_a = from_a.span(to_a);
+        let range_b = from_b.span(to_b);
 
         // Assert
         assert_eq!(
             range_a,
             vec![
-                Position2D { x: 498, y: 4 },
-                Position2D { x: 498, y: 5 },
-                Position2D { x: 498, y: 6 },
+                Position { x: 498, y: 4 },
+                Position { x: 498, y: 5 },
+                Position { x: 498, y: 6 },
             ]
         );
         assert_eq!(
             range_b,
             vec![
-                Position2D { x: 496, y: 6 },
-                Position2D { x: 497, y: 6 },
-                Position2D { x: 498, y: 6 },
+                Position { x: 496, y: 6 },
+                Position { x: 497, y: 6 },
+                Position { x: 498, y: 6 },
             ]
         );
     }
@@ -483,7 +427,7 @@ mod tests {
     fn test_vertical_cave_slice_step() {
         // Arrange
         let vertical_cave_slice_0 =
-            VerticalCaveSlice::parse_rock_scan(Position2D { x: 500, y: 0 }, TEST_INPUT).unwrap();
+            VerticalCaveSlice::parse_rock_scan(Position { x: 500, y: 0 }, TEST_INPUT).unwrap();
         assert_eq!(vertical_cave_slice_0.still_sand, HashSet::new());
 
         // Act 1
@@ -498,7 +442,7 @@ mod tests {
 
         assert_eq!(
             vertical_cave_slice_1.moving_sand,
-            Some(Position2D { x: 500, y: 0 })
+            Some(Position { x: 500, y: 0 })
         );
         assert_eq!(vertical_cave_slice_1.still_sand, HashSet::new(),);
 
@@ -514,7 +458,7 @@ mod tests {
 
         assert_eq!(
             vertical_cave_slice_2.moving_sand,
-            Some(Position2D { x: 500, y: 1 })
+            Some(Position { x: 500, y: 1 })
         );
         assert_eq!(vertical_cave_slice_2.still_sand, HashSet::new(),);
     }
@@ -523,7 +467,7 @@ mod tests {
     fn test_vertical_cave_slice_steps_till_sand_resting() {
         // Arrange
         let vertical_cave_slice_0 =
-            VerticalCaveSlice::parse_rock_scan(Position2D { x: 500, y: 0 }, TEST_INPUT).unwrap();
+            VerticalCaveSlice::parse_rock_scan(Position { x: 500, y: 0 }, TEST_INPUT).unwrap();
 
         // Act
         let vertical_cave_slice_1 = vertical_cave_slice_0.steps_till_sand_resting();
@@ -537,7 +481,7 @@ mod tests {
 
         assert_eq!(
             vertical_cave_slice_1.still_sand,
-            HashSet::from([Position2D { x: 500, y: 8 }])
+            HashSet::from([Position { x: 500, y: 8 }])
         );
     }
 
@@ -545,7 +489,7 @@ mod tests {
     fn test_vertical_cave_slice_steps_till_sand_resting_when_falling_into_bottom() {
         // Arrange
         let vertical_cave_slice =
-            VerticalCaveSlice::parse_rock_scan(Position2D { x: 200, y: 0 }, TEST_INPUT).unwrap();
+            VerticalCaveSlice::parse_rock_scan(Position { x: 200, y: 0 }, TEST_INPUT).unwrap();
 
         // Act
         let new_vertical_cave_slice = vertical_cave_slice.steps_till_sand_resting();
@@ -0,0 +1,152 @@
+//! Positions and directions on the integer grids of the puzzles, shared by the days of every year.
+
+use std::str::FromStr;
+
+use anyhow::Context;
+
+/// A cell of a grid, with `y` growing downwards like the lines of the puzzle inputs.
+///
+/// Grids indexed by the rows and columns of an input use `usize`, unbounded ones `i64`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Position<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+/// The way from one position to another.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Vector {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Vector {
+    pub const UP: Self = Self { x: 0, y: -1 };
+    pub const DOWN: Self = Self { x: 0, y: 1 };
+    pub const LEFT: Self = Self { x: -1, y: 0 };
+    pub const RIGHT: Self = Self { x: 1, y: 0 };
+
+    /// The directions to the four neighbours sharing an edge with a position.
+    pub const ORTHOGONAL: [Self; 4] = [Self::UP, Self::DOWN, Self::LEFT, Self::RIGHT];
+}
+
+impl Position<i64> {
+    /// The position `vector` away, if it does not overflow.
+    pub fn checked_add(self, vector: Vector) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add(vector.x)?,
+            y: self.y.checked_add(vector.y)?,
+        })
+    }
+
+    /// The vector leading from this position to `other`, if it does not overflow.
+    pub fn vector_to(self, other: Self) -> Option<Vector> {
+        Some(Vector {
+            x: other.x.checked_sub(self.x)?,
+            y: other.y.checked_sub(self.y)?,
+        })
+    }
+
+    /// Every position of the rectangle spanned by both positions, ordered by `x` and then `y`.
+    ///
+    /// For positions in the same row or column, this is the straight line between them.
+    pub fn span(self, other: Self) -> Vec<Self> {
+        (self.x.min(other.x)..=self.x.max(other.x))
+            .flat_map(|x| (self.y.min(other.y)..=self.y.max(other.y)).map(move |y| Self { x, y }))
+            .collect()
+    }
+}
+
+impl Position<usize> {
+    /// The position `vector` away, if it stays inside the grid at the top and the left.
+    pub fn checked_add(self, vector: Vector) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add_signed(isize::try_from(vector.x).ok()?)?,
+            y: self.y.checked_add_signed(isize::try_from(vector.y).ok()?)?,
+        })
+    }
+}
+
+/// Reads positions written like `498,4`.
+impl<T> FromStr for Position<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| anyhow::anyhow!("Position \"{s}\" is not written like \"x,y\"."))?;
+        Ok(Self {
+            x: x.trim()
+                .parse()
+                .with_context(|| format!("with x of \"{s}\""))?,
+            y: y.trim()
+                .parse()
+                .with_context(|| format!("with y of \"{s}\""))?,
+        })
+    }
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_from_str() -> anyhow::Result<()> {
+        // Act
+        let position = Position::<i64>::from_str("498,-4")?;
+
+        // Assert
+        assert_eq!(position, Position { x: 498, y: -4 });
+        assert!(Position::<i64>::from_str("498").is_err());
+        assert!(Position::<usize>::from_str("498,-4").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_span() {
+        // Arrange
+        let from = Position { x: 498, y: 6 };
+        let to = Position { x: 496, y: 6 };
+
+        // Act
+        let line = from.span(to);
+
+        // Assert
+        assert_eq!(
+            line,
+            vec![
+                Position { x: 496, y: 6 },
+                Position { x: 497, y: 6 },
+                Position { x: 498, y: 6 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_neighbours_stay_inside_the_grid() {
+        // Arrange
+        let corner = Position::<usize>::default();
+
+        // Act
+        let neighbours = Vector::ORTHOGONAL
+            .iter()
+            .filter_map(|&direction| corner.checked_add(direction))
+            .collect::<Vec<_>>();
+
+        // Assert
+        assert_eq!(
+            neighbours,
+            vec![Position { x: 0, y: 1 }, Position { x: 1, y: 0 }]
+        );
+        assert_eq!(
+            Position { x: i64::MAX, y: 0 }.checked_add(Vector::RIGHT),
+            None
+        );
+    }
+}
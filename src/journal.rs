@@ -0,0 +1,473 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::Context;
+
+/// Location of the journal data file inside the repository.
+pub fn default_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("data/journal.txt")
+}
+
+/// All recorded solve attempts, in the order they are stored in the data file.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Journal {
+    pub entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("while reading journal {}", path.display()))?
+            .parse()
+            .with_context(|| format!("while parsing journal {}", path.display()))
+    }
+
+    /// Appends an entry to the data file and returns it with the attempt number filled in.
+    pub fn record(
+        path: &Path,
+        year: u16,
+        day: u8,
+        part: u8,
+        duration: SolveDuration,
+        note: Option<String>,
+    ) -> anyhow::Result<JournalEntry> {
+        let journal = Self::load(path)?;
+        let attempt = journal
+            .entries
+            .iter()
+            .filter(|entry| entry.year == year && entry.day == day && entry.part == part)
+            .map(|entry| entry.attempt)
+            .max()
+            .unwrap_or(0)
+            .checked_add(1)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Too many attempts for day {day} part {part} of {year}.")
+            })?;
+        let entry = JournalEntry::new(year, day, part, attempt, duration, note)?;
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .with_context(|| format!("while opening journal {}", path.display()))?;
+        writeln!(file, "{entry}")
+            .with_context(|| format!("while appending to journal {}", path.display()))?;
+        Ok(entry)
+    }
+
+    pub fn report(&self) -> JournalReport {
+        let mut days: BTreeMap<(u16, u8), DayTimes> = BTreeMap::new();
+        for entry in &self.entries {
+            let day_times = days.entry((entry.year, entry.day)).or_default();
+            let part_times = if entry.part == 1 {
+                &mut day_times.part_1
+            } else {
+                &mut day_times.part_2
+            };
+            part_times.attempts = part_times.attempts.saturating_add(1);
+            part_times.duration = part_times.duration.saturating_add(entry.duration.0);
+        }
+        JournalReport { days }
+    }
+}
+
+impl FromStr for Journal {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            entries: s
+                .lines()
+                .enumerate()
+                .filter(|&(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+                .map(|(index, line)| {
+                    JournalEntry::from_str(line).with_context(|| format!("in line #{index}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+/// A single solve attempt of one puzzle part.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JournalEntry {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub attempt: u8,
+    pub duration: SolveDuration,
+    pub note: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn new(
+        year: u16,
+        day: u8,
+        part: u8,
+        attempt: u8,
+        duration: SolveDuration,
+        note: Option<String>,
+    ) -> anyhow::Result<Self> {
+        if year < 2015 {
+            return Err(anyhow::anyhow!(
+                "Year {year} is before the first Advent of Code in 2015."
+            ));
+        }
+        if !(1..=25).contains(&day) {
+            return Err(anyhow::anyhow!("Day {day} is not within 1..=25."));
+        }
+        if !(1..=2).contains(&part) {
+            return Err(anyhow::anyhow!("Part {part} is neither 1 nor 2."));
+        }
+        if attempt == 0 {
+            return Err(anyhow::anyhow!("Attempts are counted from 1."));
+        }
+        Ok(Self {
+            year,
+            day,
+            part,
+            attempt,
+            duration,
+            note: note.filter(|note| !note.trim().is_empty()),
+        })
+    }
+}
+
+impl FromStr for JournalEntry {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.splitn(6, ' ');
+        let mut next_field = |name: &str| {
+            fields
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Missing field `{name}` in \"{s}\"."))
+        };
+        let year = next_field("year")?.parse().context("with `year`")?;
+        let day = next_field("day")?.parse().context("with `day`")?;
+        let part = next_field("part")?.parse().context("with `part`")?;
+        let attempt = next_field("attempt")?.parse().context("with `attempt`")?;
+        let duration = next_field("duration")?.parse().context("with `duration`")?;
+        let note = fields.next().map(str::to_owned);
+        Self::new(year, day, part, attempt, duration, note)
+    }
+}
+
+impl Display for JournalEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:02} {} {} {}",
+            self.year, self.day, self.part, self.attempt, self.duration
+        )?;
+        if let Some(ref note) = self.note {
+            write!(f, " {note}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A solve duration written like `1h39m43s`, `27m29s` or `45s`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Default)]
+pub struct SolveDuration(pub Duration);
+
+impl FromStr for SolveDuration {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(anyhow::anyhow!("Duration is empty."));
+        }
+        let mut seconds: u64 = 0;
+        let mut number = String::new();
+        for character in s.chars() {
+            if character.is_ascii_digit() {
+                number.push(character);
+                continue;
+            }
+            let factor = match character {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                other => return Err(anyhow::anyhow!("Unexpected unit '{other}' in \"{s}\".")),
+            };
+            let value: u64 = number
+                .parse()
+                .with_context(|| format!("Missing number before '{character}' in \"{s}\"."))?;
+            seconds = value
+                .checked_mul(factor)
+                .and_then(|value_seconds| seconds.checked_add(value_seconds))
+                .ok_or_else(|| anyhow::anyhow!("Duration \"{s}\" is too long."))?;
+            number.clear();
+        }
+        if number.is_empty() {
+            Ok(Self(Duration::from_secs(seconds)))
+        } else {
+            Err(anyhow::anyhow!(
+                "Number without unit at the end of \"{s}\"."
+            ))
+        }
+    }
+}
+
+impl Display for SolveDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total_seconds = self.0.as_secs();
+        let hours = total_seconds / 3600;
+        let minutes = total_seconds % 3600 / 60;
+        let seconds = total_seconds % 60;
+        if hours > 0 {
+            write!(f, "{hours}h{minutes}m{seconds}s")
+        } else if minutes > 0 {
+            write!(f, "{minutes}m{seconds}s")
+        } else {
+            write!(f, "{seconds}s")
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct PartTimes {
+    pub attempts: u32,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct DayTimes {
+    pub part_1: PartTimes,
+    pub part_2: PartTimes,
+}
+
+impl DayTimes {
+    pub fn total(&self) -> Duration {
+        self.part_1.duration.saturating_add(self.part_2.duration)
+    }
+
+    /// How long part 2 took compared to part 1, if both have been recorded.
+    pub fn part_2_to_part_1_ratio(&self) -> Option<f64> {
+        (self.part_1.attempts > 0 && self.part_2.attempts > 0 && !self.part_1.duration.is_zero())
+            .then(|| self.part_2.duration.as_secs_f64() / self.part_1.duration.as_secs_f64())
+    }
+}
+
+/// Per day summary of the journal with totals, averages and part ratios.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JournalReport {
+    /// Times by year and day.
+    pub days: BTreeMap<(u16, u8), DayTimes>,
+}
+
+impl JournalReport {
+    pub fn total(&self) -> Duration {
+        self.days
+            .values()
+            .fold(Duration::ZERO, |sum, day| sum.saturating_add(day.total()))
+    }
+
+    pub fn part_total(&self, part: u8) -> Duration {
+        self.part_times(part).fold(Duration::ZERO, |sum, times| {
+            sum.saturating_add(times.duration)
+        })
+    }
+
+    /// Average solve time of a part over all days where it has been recorded.
+    pub fn part_average(&self, part: u8) -> Option<Duration> {
+        let count = u32::try_from(self.part_times(part).count()).ok()?;
+        self.part_total(part).checked_div(count)
+    }
+
+    pub fn day_average(&self) -> Option<Duration> {
+        let count = u32::try_from(self.days.len()).ok()?;
+        self.total().checked_div(count)
+    }
+
+    /// Ratio of all part 2 time to all part 1 time, only counting days with both parts.
+    pub fn overall_part_2_to_part_1_ratio(&self) -> Option<f64> {
+        let (part_1, part_2) = self
+            .days
+            .values()
+            .filter(|day| day.part_2_to_part_1_ratio().is_some())
+            .fold((Duration::ZERO, Duration::ZERO), |(part_1, part_2), day| {
+                (
+                    part_1.saturating_add(day.part_1.duration),
+                    part_2.saturating_add(day.part_2.duration),
+                )
+            });
+        (!part_1.is_zero()).then(|| part_2.as_secs_f64() / part_1.as_secs_f64())
+    }
+
+    fn part_times(&self, part: u8) -> impl Iterator<Item = &PartTimes> {
+        self.days
+            .values()
+            .map(move |day| if part == 1 { &day.part_1 } else { &day.part_2 })
+            .filter(|times| times.attempts > 0)
+    }
+}
+
+impl Display for JournalReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn optional_duration(duration: Option<Duration>) -> String {
+            duration.map_or_else(|| "-".to_owned(), |d| SolveDuration(d).to_string())
+        }
+        fn optional_ratio(ratio: Option<f64>) -> String {
+            ratio.map_or_else(|| "-".to_owned(), |r| format!("{r:.2}"))
+        }
+        fn part(times: &PartTimes) -> String {
+            match times.attempts {
+                0 => "-".to_owned(),
+                1 => SolveDuration(times.duration).to_string(),
+                attempts => format!("{} ({attempts}x)", SolveDuration(times.duration)),
+            }
+        }
+
+        writeln!(
+            f,
+            "{:>4} | {:>3} | {:>14} | {:>14} | {:>10} | {:>5}",
+            "year", "day", "part 1", "part 2", "total", "p2/p1"
+        )?;
+        writeln!(f, "{}", "-".repeat(65))?;
+        for (&(year, day), times) in &self.days {
+            writeln!(
+                f,
+                "{:>4} | {:>3} | {:>14} | {:>14} | {:>10} | {:>5}",
+                year,
+                day,
+                part(&times.part_1),
+                part(&times.part_2),
+                SolveDuration(times.total()).to_string(),
+                optional_ratio(times.part_2_to_part_1_ratio())
+            )?;
+        }
+        writeln!(f, "{}", "-".repeat(65))?;
+        writeln!(
+            f,
+            "total: {} (part 1: {}, part 2: {})",
+            SolveDuration(self.total()),
+            SolveDuration(self.part_total(1)),
+            SolveDuration(self.part_total(2))
+        )?;
+        writeln!(
+            f,
+            "average: {} per day (part 1: {}, part 2: {})",
+            optional_duration(self.day_average()),
+            optional_duration(self.part_average(1)),
+            optional_duration(self.part_average(2))
+        )?;
+        write!(
+            f,
+            "part 2 / part 1: {}",
+            optional_ratio(self.overall_part_2_to_part_1_ratio())
+        )
+    }
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_JOURNAL: &str = "# year day part attempt duration note
+2022 01 1 1 9m10s
+2022 01 2 1 3m56s
+2022 10 1 1 1h39m43s
+2022 10 2 1 4m4s
+2022 10 2 2 50m37s second attempt
+2022 12 1 1 30s
+2023 10 1 1 2m";
+
+    #[test]
+    fn test_solve_duration_from_str() -> anyhow::Result<()> {
+        // Act
+        let durations = ["1h39m43s", "27m29s", "45s", "2h"]
+            .into_iter()
+            .map(SolveDuration::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Assert
+        assert_eq!(
+            durations,
+            vec![
+                SolveDuration(Duration::from_secs(5983)),
+                SolveDuration(Duration::from_secs(1649)),
+                SolveDuration(Duration::from_secs(45)),
+                SolveDuration(Duration::from_secs(7200)),
+            ]
+        );
+        assert!(SolveDuration::from_str("12").is_err());
+        assert!(SolveDuration::from_str("3d").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_journal_entry_round_trip() -> anyhow::Result<()> {
+        // Arrange
+        let line = "2022 11 2 3 27m29s with the help of another solution";
+
+        // Act
+        let entry = JournalEntry::from_str(line)?;
+
+        // Assert
+        assert_eq!(
+            entry,
+            JournalEntry {
+                year: 2022,
+                day: 11,
+                part: 2,
+                attempt: 3,
+                duration: SolveDuration(Duration::from_secs(1649)),
+                note: Some("with the help of another solution".to_owned()),
+            }
+        );
+        assert_eq!(entry.to_string(), line);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_journal_report() -> anyhow::Result<()> {
+        // Arrange
+        let journal = Journal::from_str(TEST_JOURNAL)?;
+
+        // Act
+        let report = journal.report();
+
+        // Assert
+        let day_10 = report
+            .days
+            .get(&(2022, 10))
+            .ok_or_else(|| anyhow::anyhow!("Day 10 of 2022 is missing."))?;
+        assert_eq!(day_10.part_2.attempts, 2);
+        assert_eq!(
+            report
+                .days
+                .get(&(2023, 10))
+                .map(|day_10_of_2023| day_10_of_2023.part_1.attempts),
+            Some(1)
+        );
+        assert_eq!(day_10.part_2.duration, Duration::from_secs(3281));
+        assert_eq!(
+            report.total(),
+            Duration::from_secs(550 + 236 + 5983 + 3281 + 30 + 120)
+        );
+        assert_eq!(
+            report.part_average(2),
+            Some(Duration::from_millis(1_758_500))
+        );
+        assert_eq!(
+            report
+                .days
+                .get(&(2022, 12))
+                .and_then(DayTimes::part_2_to_part_1_ratio),
+            None
+        );
+        assert_eq!(
+            report.overall_part_2_to_part_1_ratio(),
+            Some(3517.0 / 6533.0)
+        );
+
+        Ok(())
+    }
+}
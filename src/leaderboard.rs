@@ -0,0 +1,558 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::Context;
+
+use serde::Deserialize;
+
+use crate::journal::{JournalReport, SolveDuration};
+
+/// A private leaderboard as exported by the "API" link on the leaderboard page.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Leaderboard {
+    pub event: i64,
+    pub members: Vec<Member>,
+}
+
+impl Leaderboard {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("while reading leaderboard {}", path.display()))?
+            .parse()
+            .with_context(|| format!("while parsing leaderboard {}", path.display()))
+    }
+
+    /// Members ordered by their local score, best first.
+    pub fn standings(&self) -> Vec<&Member> {
+        let mut standings = self.members.iter().collect::<Vec<_>>();
+        standings.sort_by_key(|member| (Reverse(member.local_score), Reverse(member.stars)));
+        standings
+    }
+
+    /// All days on which at least one member got a star.
+    pub fn days(&self) -> Vec<u8> {
+        let mut days = self
+            .members
+            .iter()
+            .flat_map(|member| member.completions.keys().copied())
+            .collect::<Vec<_>>();
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+
+    /// Finds a member by id or by name.
+    pub fn find_member(&self, id_or_name: &str) -> Option<&Member> {
+        self.members.iter().find(|member| {
+            member.id.to_string() == id_or_name || member.name.as_deref() == Some(id_or_name)
+        })
+    }
+
+    /// Local score of every member after each day, recomputed from the star timestamps.
+    ///
+    /// The first member to get a star receives as many points as there are members, the next
+    /// one point less and so on.
+    pub fn score_history(&self) -> BTreeMap<u8, HashMap<u64, u64>> {
+        let member_count = u64::try_from(self.members.len()).unwrap_or(u64::MAX);
+        let mut scores: HashMap<u64, u64> =
+            self.members.iter().map(|member| (member.id, 0)).collect();
+        let mut history = BTreeMap::new();
+        for day in self.days() {
+            for part in [1, 2] {
+                let mut finishers = self
+                    .members
+                    .iter()
+                    .filter_map(|member| {
+                        member
+                            .completions
+                            .get(&day)
+                            .and_then(|completion| completion.star(part))
+                            .map(|timestamp| (timestamp, member.id))
+                    })
+                    .collect::<Vec<_>>();
+                finishers.sort_unstable();
+                for (position, (_, id)) in (0_u64..).zip(finishers) {
+                    let points = member_count.saturating_sub(position);
+                    let score = scores.entry(id).or_insert(0);
+                    *score = score.saturating_add(points);
+                }
+            }
+            history.insert(day, scores.clone());
+        }
+        history
+    }
+
+    /// Rank of every member after each day, 1 being the best.
+    pub fn rank_history(&self) -> BTreeMap<u8, HashMap<u64, usize>> {
+        self.score_history()
+            .into_iter()
+            .map(|(day, scores)| {
+                let mut ordered = scores.into_iter().collect::<Vec<_>>();
+                ordered.sort_by_key(|&(id, score)| (Reverse(score), id));
+                let ranks = ordered
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (id, _))| (id, index.saturating_add(1)))
+                    .collect();
+                (day, ranks)
+            })
+            .collect()
+    }
+
+    /// Unix timestamp at which the puzzle of the given day is unlocked (midnight EST).
+    pub fn unlock_timestamp(&self, day: u8) -> i64 {
+        let days_since_epoch = days_from_civil(self.event, 12, i64::from(day));
+        days_since_epoch
+            .saturating_mul(86_400)
+            .saturating_add(5 * 3600)
+    }
+}
+
+impl FromStr for Leaderboard {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw: RawLeaderboard =
+            serde_json::from_str(s).context("Leaderboard is not valid JSON.")?;
+        let event = raw
+            .event
+            .parse()
+            .with_context(|| format!("Event \"{}\" is not a year.", raw.event))?;
+        let mut members = raw
+            .members
+            .into_values()
+            .map(Member::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        members.sort_by_key(|member| member.id);
+        Ok(Self { event, members })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    pub completions: BTreeMap<u8, DayCompletion>,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+}
+
+impl TryFrom<RawMember> for Member {
+    type Error = anyhow::Error;
+
+    fn try_from(value: RawMember) -> Result<Self, Self::Error> {
+        let completions = value
+            .completion_day_level
+            .into_iter()
+            .map(|(day, parts)| {
+                let day = day.parse::<u8>().with_context(|| {
+                    format!("Day \"{day}\" of member #{} is no number.", value.id)
+                })?;
+                let star = |part: &str| parts.get(part).map(|star| star.get_star_ts);
+                Ok((
+                    day,
+                    DayCompletion {
+                        part_1: star("1"),
+                        part_2: star("2"),
+                    },
+                ))
+            })
+            .collect::<anyhow::Result<BTreeMap<_, _>>>()?;
+        Ok(Self {
+            id: value.id,
+            name: value.name,
+            stars: value.stars,
+            local_score: value.local_score,
+            completions,
+        })
+    }
+}
+
+/// Unix timestamps at which a member got the stars of one day.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct DayCompletion {
+    pub part_1: Option<i64>,
+    pub part_2: Option<i64>,
+}
+
+impl DayCompletion {
+    pub fn star(&self, part: u8) -> Option<i64> {
+        match part {
+            1 => self.part_1,
+            2 => self.part_2,
+            _ => None,
+        }
+    }
+
+    /// Seconds between getting the first and the second star.
+    pub fn part_2_delta(&self) -> Option<i64> {
+        self.part_2?.checked_sub(self.part_1?)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLeaderboard {
+    event: String,
+    members: HashMap<String, RawMember>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawMember {
+    id: u64,
+    name: Option<String>,
+    stars: u32,
+    local_score: u64,
+    #[serde(default)]
+    completion_day_level: HashMap<String, HashMap<String, RawStar>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawStar {
+    get_star_ts: i64,
+}
+
+/// Number of days since 1970-01-01 for a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Formats seconds like the leaderboard does, e.g. `01:02:03` or `>24h`.
+fn format_elapsed(seconds: Option<i64>) -> String {
+    match seconds {
+        None => "-".to_owned(),
+        Some(seconds) if seconds >= 86_400 => ">24h".to_owned(),
+        Some(seconds) if seconds < 0 => "?".to_owned(),
+        Some(seconds) => format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        ),
+    }
+}
+
+/// Everything the `leaderboard` command prints.
+pub struct LeaderboardReport<'data> {
+    pub leaderboard: &'data Leaderboard,
+    pub chart: bool,
+    pub own_member: Option<&'data Member>,
+    pub journal: Option<&'data JournalReport>,
+}
+
+impl LeaderboardReport<'_> {
+    fn fmt_standings(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Standings {}", self.leaderboard.event)?;
+        for (index, member) in self.leaderboard.standings().into_iter().enumerate() {
+            writeln!(
+                f,
+                "{:>3}) {:>5} {:>2}* {}",
+                index.saturating_add(1),
+                member.local_score,
+                member.stars,
+                member.display_name()
+            )?;
+        }
+        Ok(())
+    }
+
+    fn fmt_star_times(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for day in self.leaderboard.days() {
+            let unlock = self.leaderboard.unlock_timestamp(day);
+            writeln!(f)?;
+            writeln!(f, "Day {day:>2}   part 1   part 2    delta")?;
+            let mut finishers = self
+                .leaderboard
+                .members
+                .iter()
+                .filter_map(|member| member.completions.get(&day).map(|c| (member, c)))
+                .collect::<Vec<_>>();
+            finishers.sort_by_key(|&(member, completion)| {
+                (
+                    completion.part_2.is_none(),
+                    completion.part_2,
+                    completion.part_1,
+                    member.id,
+                )
+            });
+            for (member, completion) in finishers {
+                writeln!(
+                    f,
+                    "       {:>8} {:>8} {:>8} {}",
+                    format_elapsed(completion.part_1.map(|ts| ts.saturating_sub(unlock))),
+                    format_elapsed(completion.part_2.map(|ts| ts.saturating_sub(unlock))),
+                    format_elapsed(completion.part_2_delta()),
+                    member.display_name()
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fmt_rank_table(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rank_history = self.leaderboard.rank_history();
+        write!(f, "Rank after day")?;
+        for day in rank_history.keys() {
+            write!(f, " {day:>3}")?;
+        }
+        writeln!(f)?;
+        for member in self.leaderboard.standings() {
+            write!(f, "{:>14}", truncate(&member.display_name(), 14))?;
+            for ranks in rank_history.values() {
+                match ranks.get(&member.id) {
+                    Some(rank) => write!(f, " {rank:>3}")?,
+                    None => write!(f, "   -")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+
+    /// Bump chart with one row per rank and one column per day, members drawn as letters.
+    fn fmt_rank_chart(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rank_history = self.leaderboard.rank_history();
+        let standings = self.leaderboard.standings();
+        let symbols = ('A'..='Z').chain('a'..='z').chain('0'..='9');
+        let legend = standings
+            .iter()
+            .zip(symbols)
+            .map(|(member, symbol)| (member.id, (symbol, member.display_name())))
+            .collect::<BTreeMap<_, _>>();
+
+        writeln!(f, "Rank history")?;
+        for rank in 1..=standings.len() {
+            write!(f, "{rank:>4} |")?;
+            for ranks in rank_history.values() {
+                let symbol = ranks
+                    .iter()
+                    .find(|&(_, &member_rank)| member_rank == rank)
+                    .and_then(|(id, _)| legend.get(id))
+                    .map_or('.', |&(symbol, _)| symbol);
+                write!(f, "  {symbol}")?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "     +")?;
+        for _ in rank_history.keys() {
+            write!(f, "---")?;
+        }
+        writeln!(f)?;
+        write!(f, "  day ")?;
+        for day in rank_history.keys() {
+            write!(f, " {day:>2}")?;
+        }
+        writeln!(f)?;
+        for member in &standings {
+            if let Some(&(symbol, ref name)) = legend.get(&member.id) {
+                writeln!(f, "  {symbol} = {name}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fmt_cross_reference(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        member: &Member,
+    ) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} compared to the solve time journal",
+            member.display_name()
+        )?;
+        writeln!(
+            f,
+            "day | star 1 after | journal 1 | star 2 delta | journal 2"
+        )?;
+        let year = u16::try_from(self.leaderboard.event).unwrap_or_default();
+        for day in crate::registry::implemented_days(year) {
+            let completion = member.completions.get(&day).copied().unwrap_or_default();
+            let unlock = self.leaderboard.unlock_timestamp(day);
+            let journal_day = self
+                .journal
+                .and_then(|journal| journal.days.get(&(year, day)));
+            let journal_part = |part: u8| {
+                journal_day
+                    .map(|times| {
+                        if part == 1 {
+                            times.part_1
+                        } else {
+                            times.part_2
+                        }
+                    })
+                    .filter(|times| times.attempts > 0)
+                    .map_or_else(
+                        || "-".to_owned(),
+                        |times| SolveDuration(times.duration).to_string(),
+                    )
+            };
+            writeln!(
+                f,
+                "{:>3} | {:>12} | {:>9} | {:>12} | {:>9}",
+                day,
+                format_elapsed(completion.part_1.map(|ts| ts.saturating_sub(unlock))),
+                journal_part(1),
+                format_elapsed(completion.part_2_delta()),
+                journal_part(2)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for LeaderboardReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_standings(f)?;
+        self.fmt_star_times(f)?;
+        writeln!(f)?;
+        if self.chart {
+            self.fmt_rank_chart(f)?;
+        } else {
+            self.fmt_rank_table(f)?;
+        }
+        if let Some(member) = self.own_member {
+            writeln!(f)?;
+            self.fmt_cross_reference(f, member)?;
+        }
+        Ok(())
+    }
+}
+
+fn truncate(text: &str, length: usize) -> String {
+    text.chars().take(length).collect()
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = r#"{
+  "owner_id": 1,
+  "event": "2022",
+  "members": {
+    "1": {
+      "id": 1,
+      "name": "Alice",
+      "stars": 4,
+      "local_score": 11,
+      "global_score": 0,
+      "last_star_ts": 1669960000,
+      "completion_day_level": {
+        "1": {
+          "1": { "get_star_ts": 1669871000, "star_index": 10 },
+          "2": { "get_star_ts": 1669871600, "star_index": 11 }
+        },
+        "2": {
+          "1": { "get_star_ts": 1669958000, "star_index": 20 },
+          "2": { "get_star_ts": 1669960000, "star_index": 21 }
+        }
+      }
+    },
+    "2": {
+      "id": 2,
+      "name": null,
+      "stars": 3,
+      "local_score": 10,
+      "global_score": 0,
+      "last_star_ts": 1669957500,
+      "completion_day_level": {
+        "1": {
+          "1": { "get_star_ts": 1669870900, "star_index": 5 }
+        },
+        "2": {
+          "1": { "get_star_ts": 1669957300, "star_index": 15 },
+          "2": { "get_star_ts": 1669957500, "star_index": 16 }
+        }
+      }
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_leaderboard_from_str() -> anyhow::Result<()> {
+        // Act
+        let leaderboard = Leaderboard::from_str(TEST_INPUT)?;
+
+        // Assert
+        assert_eq!(leaderboard.event, 2022);
+        assert_eq!(leaderboard.members.len(), 2);
+        assert_eq!(leaderboard.days(), vec![1, 2]);
+        let anonymous = leaderboard
+            .find_member("2")
+            .ok_or_else(|| anyhow::anyhow!("Member #2 is missing."))?;
+        assert_eq!(anonymous.display_name(), "(anonymous user #2)");
+        assert_eq!(
+            anonymous.completions.get(&1),
+            Some(&DayCompletion {
+                part_1: Some(1_669_870_900),
+                part_2: None,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_leaderboard_unlock_timestamp() -> anyhow::Result<()> {
+        // Arrange
+        let leaderboard = Leaderboard::from_str(TEST_INPUT)?;
+
+        // Act
+        let unlock = leaderboard.unlock_timestamp(1);
+
+        // Assert
+        assert_eq!(unlock, 1_669_870_800); // 2022-12-01T05:00:00Z
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_leaderboard_score_and_rank_history() -> anyhow::Result<()> {
+        // Arrange
+        let leaderboard = Leaderboard::from_str(TEST_INPUT)?;
+
+        // Act
+        let score_history = leaderboard.score_history();
+        let rank_history = leaderboard.rank_history();
+
+        // Assert
+        assert_eq!(
+            score_history.get(&1),
+            Some(&HashMap::from([(1, 3), (2, 2)]))
+        );
+        assert_eq!(
+            score_history.get(&2),
+            Some(&HashMap::from([(1, 5), (2, 6)]))
+        );
+        assert_eq!(rank_history.get(&1), Some(&HashMap::from([(1, 1), (2, 2)])));
+        assert_eq!(rank_history.get(&2), Some(&HashMap::from([(1, 2), (2, 1)])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        // Assert
+        assert_eq!(format_elapsed(Some(3723)), "01:02:03");
+        assert_eq!(format_elapsed(Some(90_000)), ">24h");
+        assert_eq!(format_elapsed(None), "-");
+    }
+}
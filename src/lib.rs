@@ -0,0 +1,18 @@
+//! Shared helpers for the daily puzzle binaries.
+
+pub mod cli;
+pub mod grid;
+pub mod journal;
+pub mod leaderboard;
+pub mod logging;
+pub mod manifest;
+pub mod parse;
+pub mod profile;
+pub mod profiling;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod sample;
+pub mod scaffold;
+pub mod stream;
+pub mod vault;
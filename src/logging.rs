@@ -0,0 +1,65 @@
+use anyhow::Context;
+
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+const DEFAULT_DIRECTIVES: &str = "warn";
+
+/// Installs a global tracing subscriber writing to stderr, so stdout only carries the answers.
+///
+/// With the `alloc-stats` feature the stats of every part are printed to stderr as well.
+pub fn init(directives: Option<&str>) -> anyhow::Result<()> {
+    let registry = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(build_filter(directives)?),
+    );
+    #[cfg(feature = "alloc-stats")]
+    let registry = registry.with(crate::profiling::PartStatsLayer.with_filter(
+        tracing_subscriber::filter::filter_fn(|metadata| {
+            metadata.is_span() && metadata.name() == "part"
+        }),
+    ));
+    registry
+        .try_init()
+        .map_err(|error| anyhow::anyhow!("Could not install tracing subscriber ({error})."))
+}
+
+fn build_filter(directives: Option<&str>) -> anyhow::Result<EnvFilter> {
+    match directives {
+        Some(directives) => EnvFilter::try_new(directives)
+            .with_context(|| format!("while parsing log directives \"{directives}\"")),
+        None => Ok(EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| EnvFilter::new(DEFAULT_DIRECTIVES))),
+    }
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_filter_with_module_directives() -> anyhow::Result<()> {
+        // Act
+        let filter = build_filter(Some("day05=debug,day11=trace"))?;
+
+        // Assert
+        assert_eq!(
+            filter.max_level_hint(),
+            Some(tracing::level_filters::LevelFilter::TRACE)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_filter_with_invalid_directives() {
+        // Act
+        let filter = build_filter(Some("day05=loud"));
+
+        // Assert
+        assert!(filter.is_err());
+    }
+}
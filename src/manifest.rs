@@ -0,0 +1,376 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+
+use anyhow::Context;
+
+use serde::{Deserialize, Serialize};
+
+use sha2::{Digest, Sha256};
+
+use crate::runner::Run;
+use crate::{profile, registry, sample};
+
+/// Everything an answer depends on, recorded for every run of a runner invocation.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub crate_version: String,
+    /// Commit the runner was built from, suffixed with `-dirty` for local changes.
+    pub git_commit: Option<String>,
+    /// Cargo features the runner was built with.
+    pub features: Vec<String>,
+    pub entries: Vec<Entry>,
+}
+
+/// One solution binary run against the input of one profile.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub year: u16,
+    pub day: u8,
+    pub binary: String,
+    pub profile: String,
+    /// Hex encoded SHA-256 of the plain input, if it could be read.
+    pub input_sha256: Option<String>,
+    pub status: String,
+    pub answers: BTreeMap<String, String>,
+    pub elapsed_micros: Option<u64>,
+    /// Time of each part, with the `alloc-stats` feature.
+    pub part_micros: BTreeMap<u64, u64>,
+}
+
+impl Manifest {
+    pub fn from_runs(runs: &[Run]) -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_owned(),
+            git_commit: git_commit(),
+            features: compiled_features(),
+            entries: runs.iter().map(Entry::from_run).collect(),
+        }
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("while reading manifest {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Manifest {} is not valid JSON.", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self).context("while serializing manifest")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("while writing manifest {}", path.display()))
+    }
+
+    /// Whether both manifests were made by the same code, as far as they tell.
+    fn has_same_code(&self, other: &Self) -> bool {
+        self.crate_version == other.crate_version
+            && self.git_commit == other.git_commit
+            && self.features == other.features
+    }
+}
+
+impl Entry {
+    fn from_run(run: &Run) -> Self {
+        Self {
+            year: run.solution.year,
+            day: run.solution.day,
+            binary: run.solution.binary.to_owned(),
+            profile: run.profile.clone(),
+            input_sha256: input_sha256(run.solution.year, &run.profile, &run.solution.input_name()),
+            status: run.outcome.status().to_owned(),
+            answers: run
+                .outcome
+                .actual()
+                .map(|actual| sample::answers_in_order(actual).into_iter().collect())
+                .unwrap_or_default(),
+            elapsed_micros: run.elapsed.map(duration_micros),
+            part_micros: run
+                .parts
+                .iter()
+                .map(|part| (part.part, duration_micros(part.elapsed)))
+                .collect(),
+        }
+    }
+
+    fn key(&self) -> (u16, &str, &str) {
+        (self.year, &self.binary, &self.profile)
+    }
+}
+
+fn duration_micros(duration: std::time::Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// Hash of the input, decrypted from the vault if needed.
+fn input_sha256(year: u16, profile: &str, input_name: &str) -> Option<String> {
+    if !profile::has_input(year, profile, input_name) {
+        return None;
+    }
+    let mut reader = profile::open_input(year, profile, input_name)
+        .map_err(|error| tracing::warn!(?error, "could not hash input"))
+        .ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut reader, &mut hasher)
+        .map_err(|error| tracing::warn!(?error, "could not hash input"))
+        .ok()?;
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// The commit the runner was built from, recorded by the build script if git was available.
+fn git_commit() -> Option<String> {
+    option_env!("AOC_GIT_COMMIT").map(str::to_owned)
+}
+
+/// The enabled days and optional features of this build.
+fn compiled_features() -> Vec<String> {
+    let mut features = registry::years()
+        .into_iter()
+        .flat_map(registry::solutions)
+        .filter(|solution| solution.enabled)
+        .map(|solution| format!("day{:02}", solution.day))
+        .collect::<Vec<_>>();
+    features.dedup();
+    for (feature, enabled) in [
+        ("parallel", cfg!(feature = "parallel")),
+        ("alloc-stats", cfg!(feature = "alloc-stats")),
+    ] {
+        if enabled {
+            features.push(feature.to_owned());
+        }
+    }
+    features
+}
+
+/// What changed between two manifests for one binary and profile.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Change<'manifest> {
+    Added(&'manifest Entry),
+    Removed(&'manifest Entry),
+    Answers {
+        old: &'manifest Entry,
+        new: &'manifest Entry,
+        cause: Cause,
+    },
+}
+
+/// Why answers changed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Cause {
+    Input,
+    Code,
+    InputAndCode,
+    /// Neither the input nor the code changed, so the solution is not deterministic.
+    Unknown,
+}
+
+impl Display for Cause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            Self::Input => "input changed",
+            Self::Code => "code changed",
+            Self::InputAndCode => "input and code changed",
+            Self::Unknown => "same input and code",
+        })
+    }
+}
+
+/// The entries whose answers differ, were added or were removed, in the order of the keys.
+pub fn compare<'manifest>(
+    old: &'manifest Manifest,
+    new: &'manifest Manifest,
+) -> Vec<Change<'manifest>> {
+    let old_entries = old
+        .entries
+        .iter()
+        .map(|entry| (entry.key(), entry))
+        .collect::<BTreeMap<_, _>>();
+    let new_entries = new
+        .entries
+        .iter()
+        .map(|entry| (entry.key(), entry))
+        .collect::<BTreeMap<_, _>>();
+    let mut keys = old_entries
+        .keys()
+        .chain(new_entries.keys())
+        .copied()
+        .collect::<Vec<_>>();
+    keys.sort_unstable();
+    keys.dedup();
+    let is_same_code = old.has_same_code(new);
+    keys.into_iter()
+        .filter_map(|key| match (old_entries.get(&key), new_entries.get(&key)) {
+            (Some(&old), Some(&new)) if old.answers != new.answers => {
+                let is_same_input = old.input_sha256 == new.input_sha256;
+                let cause = match (is_same_input, is_same_code) {
+                    (false, true) => Cause::Input,
+                    (true, false) => Cause::Code,
+                    (false, false) => Cause::InputAndCode,
+                    (true, true) => Cause::Unknown,
+                };
+                Some(Change::Answers { old, new, cause })
+            }
+            (Some(_), Some(_)) | (None, None) => None,
+            (Some(&old), None) => Some(Change::Removed(old)),
+            (None, Some(&new)) => Some(Change::Added(new)),
+        })
+        .collect()
+}
+
+/// Differences in code and answers between two manifests.
+pub struct Comparison<'manifest> {
+    pub old: &'manifest Manifest,
+    pub new: &'manifest Manifest,
+}
+
+impl Display for Comparison<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let commit = |manifest: &Manifest| {
+            manifest
+                .git_commit
+                .clone()
+                .unwrap_or_else(|| "?".to_owned())
+        };
+        writeln!(
+            f,
+            "version: {} -> {}",
+            self.old.crate_version, self.new.crate_version
+        )?;
+        writeln!(f, "commit: {} -> {}", commit(self.old), commit(self.new))?;
+        for feature in self
+            .old
+            .features
+            .iter()
+            .filter(|feature| !self.new.features.contains(feature))
+        {
+            writeln!(f, "feature disabled: {feature}")?;
+        }
+        for feature in self
+            .new
+            .features
+            .iter()
+            .filter(|feature| !self.old.features.contains(feature))
+        {
+            writeln!(f, "feature enabled: {feature}")?;
+        }
+        let changes = compare(self.old, self.new);
+        for change in &changes {
+            match *change {
+                Change::Added(entry) => {
+                    writeln!(
+                        f,
+                        "{} {} {}: added",
+                        entry.year, entry.binary, entry.profile
+                    )?;
+                }
+                Change::Removed(entry) => {
+                    writeln!(
+                        f,
+                        "{} {} {}: removed",
+                        entry.year, entry.binary, entry.profile
+                    )?;
+                }
+                Change::Answers { old, new, cause } => {
+                    writeln!(f, "{} {} {}: {cause}", new.year, new.binary, new.profile)?;
+                    let labels = old
+                        .answers
+                        .keys()
+                        .chain(new.answers.keys())
+                        .collect::<std::collections::BTreeSet<_>>();
+                    for label in labels {
+                        let old_answer = old.answers.get(label);
+                        let new_answer = new.answers.get(label);
+                        if old_answer != new_answer {
+                            writeln!(
+                                f,
+                                "    {label}: {} -> {}",
+                                old_answer.map_or("-", String::as_str),
+                                new_answer.map_or("-", String::as_str)
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+        write!(f, "{} changed entries", changes.len())
+    }
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(profile: &str, input_sha256: &str, answer: &str) -> Entry {
+        Entry {
+            year: 2022,
+            day: 5,
+            binary: "day05".to_owned(),
+            profile: profile.to_owned(),
+            input_sha256: Some(input_sha256.to_owned()),
+            status: "ok".to_owned(),
+            answers: BTreeMap::from([("part_1_solution".to_owned(), answer.to_owned())]),
+            elapsed_micros: Some(1000),
+            part_micros: BTreeMap::new(),
+        }
+    }
+
+    fn manifest(git_commit: &str, entries: Vec<Entry>) -> Manifest {
+        Manifest {
+            crate_version: "0.1.0".to_owned(),
+            git_commit: Some(git_commit.to_owned()),
+            features: vec!["day05".to_owned()],
+            entries,
+        }
+    }
+
+    #[test]
+    fn test_compare_points_to_input_or_code() {
+        // Arrange
+        let old = manifest(
+            "abc",
+            vec![entry("alice", "1", "CMZ"), entry("default", "2", "MCD")],
+        );
+        let input_changed = manifest(
+            "abc",
+            vec![entry("alice", "3", "XYZ"), entry("default", "2", "MCD")],
+        );
+        let code_changed = manifest("def", vec![entry("alice", "1", "XYZ")]);
+
+        // Act
+        let input_changes = compare(&old, &input_changed);
+        let code_changes = compare(&old, &code_changed);
+
+        // Assert
+        assert!(matches!(
+            input_changes.as_slice(),
+            [Change::Answers {
+                cause: Cause::Input,
+                ..
+            }]
+        ));
+        assert!(matches!(
+            code_changes.as_slice(),
+            [
+                Change::Answers {
+                    cause: Cause::Code,
+                    ..
+                },
+                Change::Removed(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_manifest_round_trip() -> anyhow::Result<()> {
+        // Arrange
+        let manifest = manifest("abc", vec![entry("default", "2", "MCD")]);
+
+        // Act
+        let parsed: Manifest = serde_json::from_str(&serde_json::to_string(&manifest)?)?;
+
+        // Assert
+        assert_eq!(parsed, manifest);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,375 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Byte range of the source text a parser consumed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// What a parser expected, located by line and column of the whole source text.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Parses the whole source text, failing if the parser leaves anything but a final newline.
+pub fn all<'a, T>(
+    source: &'a str,
+    parser: impl FnOnce(&mut Cursor<'a>) -> ParseResult<T>,
+) -> ParseResult<T> {
+    let mut cursor = Cursor::new(source.strip_suffix('\n').unwrap_or(source));
+    let parsed = parser(&mut cursor)?;
+    cursor.finish()?;
+    Ok(parsed)
+}
+
+/// Position in a source text that the parsers advance.
+///
+/// Cursors of lines and blocks still refer to the whole source text, so errors and spans keep
+/// their absolute positions.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a> {
+    source: &'a str,
+    position: usize,
+    end: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            position: 0,
+            end: source.len(),
+        }
+    }
+
+    /// The text not consumed yet.
+    pub fn rest(&self) -> &'a str {
+        self.source.get(self.position..self.end).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.end
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// An error at the current position.
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.position, message)
+    }
+
+    /// An error at a byte offset of the source text.
+    pub fn error_at(&self, offset: usize, message: impl Into<String>) -> ParseError {
+        let before = self.source.get(..offset).unwrap_or(self.source);
+        let line_start = before
+            .rfind('\n')
+            .map_or(0, |newline| newline.saturating_add(1));
+        ParseError {
+            line: before.matches('\n').count().saturating_add(1),
+            column: before
+                .get(line_start..)
+                .unwrap_or_default()
+                .chars()
+                .count()
+                .saturating_add(1),
+            message: message.into(),
+        }
+    }
+
+    /// Fails unless everything was consumed.
+    pub fn finish(&self) -> ParseResult<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected the end, found \"{}\"", self.rest())))
+        }
+    }
+
+    /// Runs the parser and returns its result together with the consumed span.
+    pub fn spanned<T>(
+        &mut self,
+        parser: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<(T, Span)> {
+        let start = self.position;
+        let parsed = parser(self)?;
+        Ok((
+            parsed,
+            Span {
+                start,
+                end: self.position,
+            },
+        ))
+    }
+
+    /// Consumes the token if the rest starts with it.
+    pub fn try_tag(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.position = self.position.saturating_add(token.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes the token or fails.
+    pub fn tag(&mut self, token: &str) -> ParseResult<Span> {
+        let start = self.position;
+        if self.try_tag(token) {
+            Ok(Span {
+                start,
+                end: self.position,
+            })
+        } else {
+            Err(self.error(format!("expected \"{token}\"")))
+        }
+    }
+
+    /// Consumes the longest prefix whose characters all match the predicate, which may be empty.
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let length = rest
+            .find(|character| !predicate(character))
+            .unwrap_or(rest.len());
+        self.position = self.position.saturating_add(length);
+        rest.get(..length).unwrap_or_default()
+    }
+
+    /// Consumes a non-empty run of characters up to the next whitespace.
+    pub fn word(&mut self) -> ParseResult<&'a str> {
+        let word = self.take_while(|character| !character.is_whitespace());
+        if word.is_empty() {
+            Err(self.error("expected a word"))
+        } else {
+            Ok(word)
+        }
+    }
+
+    /// Consumes everything up to the end of the current line, which may be empty.
+    pub fn rest_of_line(&mut self) -> &'a str {
+        self.take_while(|character| character != '\n')
+    }
+
+    /// Consumes an optionally negative decimal number.
+    pub fn number<T: FromStr>(&mut self) -> ParseResult<T> {
+        let start = self.position;
+        let rest = self.rest();
+        let sign_length = usize::from(
+            rest.starts_with('-')
+                && rest
+                    .get(1..)
+                    .is_some_and(|digits| digits.starts_with(|digit: char| digit.is_ascii_digit())),
+        );
+        self.position = self.position.saturating_add(sign_length);
+        let digits = self.take_while(|character| character.is_ascii_digit());
+        if digits.is_empty() {
+            self.position = start;
+            return Err(self.error("expected a number"));
+        }
+        let text = self.source.get(start..self.position).unwrap_or_default();
+        text.parse()
+            .map_err(|_| self.error_at(start, format!("{text} is out of range")))
+    }
+
+    /// Consumes one or more items between separators.
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.try_tag(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Consumes the open token, the inner parser and the close token.
+    pub fn bracketed<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        inner: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        self.tag(open)?;
+        let parsed = inner(self)?;
+        self.tag(close)?;
+        Ok(parsed)
+    }
+
+    /// Consumes a possibly empty list like `[1,[2,3],[]]`; the item parser may recurse into it.
+    pub fn list<T>(
+        &mut self,
+        open: &str,
+        separator: &str,
+        close: &str,
+        item: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<T>> {
+        self.bracketed(open, close, |cursor| {
+            if cursor.rest().starts_with(close) {
+                Ok(Vec::new())
+            } else {
+                cursor.separated(separator, item)
+            }
+        })
+    }
+
+    /// Consumes the rest of the current line including its newline.
+    pub fn line(&mut self) -> ParseResult<Self> {
+        if self.is_empty() {
+            return Err(self.error("expected another line"));
+        }
+        let line_end = self
+            .rest()
+            .find('\n')
+            .map_or(self.end, |offset| self.position.saturating_add(offset));
+        let line = Self {
+            end: line_end,
+            ..*self
+        };
+        self.position = line_end.saturating_add(1).min(self.end);
+        Ok(line)
+    }
+
+    /// Consumes all remaining lines.
+    pub fn lines(&mut self) -> Vec<Self> {
+        let mut lines = Vec::new();
+        while let Ok(line) = self.line() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Consumes all remaining blocks, which are separated by blank lines.
+    pub fn blocks(&mut self) -> Vec<Self> {
+        let mut blocks = Vec::new();
+        while !self.is_empty() {
+            let block_end = self
+                .rest()
+                .find("\n\n")
+                .map_or(self.end, |offset| self.position.saturating_add(offset));
+            blocks.push(Self {
+                end: block_end,
+                ..*self
+            });
+            self.position = block_end.saturating_add(2).min(self.end);
+        }
+        blocks
+    }
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Eq, PartialEq)]
+    enum Nested {
+        Integer(u8),
+        List(Vec<Nested>),
+    }
+
+    fn nested(cursor: &mut Cursor<'_>) -> ParseResult<Nested> {
+        if cursor.rest().starts_with('[') {
+            cursor.list("[", ",", "]", nested).map(Nested::List)
+        } else {
+            cursor.number().map(Nested::Integer)
+        }
+    }
+
+    #[test]
+    fn test_tokens_and_numbers() -> anyhow::Result<()> {
+        // Act
+        let step = all("move 12 from -3 to 4\n", |cursor| {
+            cursor.tag("move ")?;
+            let count: u8 = cursor.number()?;
+            cursor.tag(" from ")?;
+            let from: i8 = cursor.number()?;
+            cursor.tag(" to ")?;
+            let (to, span): (u8, _) = cursor.spanned(Cursor::number)?;
+            Ok((count, from, to, span))
+        })?;
+
+        // Assert
+        assert_eq!(step, (12, -3, 4, Span { start: 19, end: 20 }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_lists() -> anyhow::Result<()> {
+        // Act
+        let parsed = all("[1,[2,[]],3]", nested)?;
+
+        // Assert
+        assert_eq!(
+            parsed,
+            Nested::List(vec![
+                Nested::Integer(1),
+                Nested::List(vec![Nested::Integer(2), Nested::List(Vec::new())]),
+                Nested::Integer(3),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_blocks_and_lines() -> anyhow::Result<()> {
+        // Act
+        let sums = all("1\n2\n\n3\n", |cursor| {
+            cursor
+                .blocks()
+                .into_iter()
+                .map(|mut block| {
+                    block
+                        .lines()
+                        .into_iter()
+                        .map(|mut line| line.number::<u32>())
+                        .sum::<ParseResult<u32>>()
+                })
+                .collect::<ParseResult<Vec<_>>>()
+        })?;
+
+        // Assert
+        assert_eq!(sums, vec![3, 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_location() {
+        // Act
+        let error = all("[1,2]\n[3,x]", |cursor| cursor.separated("\n", nested));
+
+        // Assert
+        assert_eq!(
+            error,
+            Err(ParseError {
+                line: 2,
+                column: 4,
+                message: "expected a number".to_owned(),
+            })
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::ffi::OsStr;
+use std::io::BufRead;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context;
+
+/// The profile whose files live directly in the directory of the year.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The profile of the example inputs and answers from the puzzle descriptions in `samples/<year>/`.
+pub const SAMPLE_PROFILE: &str = "sample";
+
+/// The profile name if it is a single plain directory name, so that its directory stays inside
+/// `inputs/<year>/`.
+pub fn parse_name(name: &str) -> anyhow::Result<String> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(component)), None) if component == OsStr::new(name) => {
+            Ok(name.to_owned())
+        }
+        _ => Err(anyhow::anyhow!(
+            "Profile \"{name}\" is not a plain directory name."
+        )),
+    }
+}
+
+/// Location of the puzzle inputs of a year inside the repository.
+pub fn inputs_dir(year: u16) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("inputs")
+        .join(year.to_string())
+}
+
+/// Directory holding the inputs and expected answers of a profile.
+pub fn profile_dir(year: u16, profile: &str) -> PathBuf {
+    match profile {
+        DEFAULT_PROFILE => inputs_dir(year),
+        SAMPLE_PROFILE => Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("samples")
+            .join(year.to_string()),
+        _ => inputs_dir(year).join(profile),
+    }
+}
+
+pub fn input_path(year: u16, profile: &str, input_name: &str) -> PathBuf {
+    profile_dir(year, profile).join(format!("{input_name}.input"))
+}
+
+/// The expected output of every solution binary reading `input_name`.
+pub fn answer_path(year: u16, profile: &str, input_name: &str) -> PathBuf {
+    profile_dir(year, profile).join(format!("{input_name}.answer"))
+}
+
+/// Whether the profile has the input, either in plain text or in the vault.
+pub fn has_input(year: u16, profile: &str, input_name: &str) -> bool {
+    let path = input_path(year, profile, input_name);
+    path.exists() || crate::vault::vault_path(&path).exists()
+}
+
+/// Reads the input, decrypting it from the vault if there is no plain text version.
+pub fn load_input(year: u16, profile: &str, input_name: &str) -> anyhow::Result<String> {
+    parse_name(profile)?;
+    let path = input_path(year, profile, input_name);
+    crate::vault::read(&path, &crate::vault::default_key_path())
+        .with_context(|| format!("while loading input {input_name} of profile {profile} in {year}"))
+}
+
+/// Opens the input for reading line by line; only vaults are decrypted into memory.
+pub fn open_input(year: u16, profile: &str, input_name: &str) -> anyhow::Result<Box<dyn BufRead>> {
+    parse_name(profile)?;
+    let path = input_path(year, profile, input_name);
+    crate::vault::open(&path, &crate::vault::default_key_path())
+        .with_context(|| format!("while opening input {input_name} of profile {profile} in {year}"))
+}
+
+/// Plain paths of every input of the profile, whether currently encrypted or not.
+pub fn input_paths(year: u16, profile: &str) -> anyhow::Result<Vec<PathBuf>> {
+    parse_name(profile)?;
+    let dir = profile_dir(year, profile);
+    let mut paths = std::fs::read_dir(&dir)
+        .with_context(|| format!("while listing {}", dir.display()))?
+        .map(|entry| {
+            let path = entry
+                .with_context(|| format!("while listing {}", dir.display()))?
+                .path();
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let plain_name = file_name
+                .strip_suffix(&format!(".{}", crate::vault::EXTENSION))
+                .unwrap_or(&file_name);
+            Ok(plain_name.ends_with(".input").then(|| dir.join(plain_name)))
+        })
+        .filter_map(Result::transpose)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    paths.sort_unstable();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// The expected answer, if one is recorded for the profile.
+pub fn load_answer(year: u16, profile: &str, input_name: &str) -> anyhow::Result<Option<String>> {
+    parse_name(profile)?;
+    let path = answer_path(year, profile, input_name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    std::fs::read_to_string(&path)
+        .map(Some)
+        .with_context(|| format!("while reading answer {}", path.display()))
+}
+
+/// The default and the sample profile followed by every subdirectory of `inputs/<year>/`, sorted
+/// by name.
+pub fn profiles(year: u16) -> anyhow::Result<Vec<String>> {
+    profiles_in(&inputs_dir(year))
+}
+
+/// Fails for subdirectories named like the default or the sample profile, as they would be
+/// shadowed by them.
+fn profiles_in(dir: &Path) -> anyhow::Result<Vec<String>> {
+    let mut profiles = std::fs::read_dir(dir)
+        .with_context(|| format!("while listing {}", dir.display()))?
+        .map(|entry| {
+            let entry = entry.with_context(|| format!("while listing {}", dir.display()))?;
+            let is_dir = entry
+                .file_type()
+                .with_context(|| format!("while inspecting {}", entry.path().display()))?
+                .is_dir();
+            Ok(is_dir.then(|| entry.file_name().to_string_lossy().into_owned()))
+        })
+        .filter_map(Result::transpose)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if let Some(reserved) = profiles
+        .iter()
+        .find(|profile| [DEFAULT_PROFILE, SAMPLE_PROFILE].contains(&profile.as_str()))
+    {
+        return Err(anyhow::anyhow!(
+            "{} is shadowed by the {reserved} profile and needs another name.",
+            dir.join(reserved).display()
+        ));
+    }
+    profiles.sort_unstable();
+    profiles.insert(0, DEFAULT_PROFILE.to_owned());
+    profiles.insert(1, SAMPLE_PROFILE.to_owned());
+    Ok(profiles)
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_paths() {
+        // Act
+        let default_input = input_path(2022, DEFAULT_PROFILE, "day05");
+        let other_answer = answer_path(2022, "alice", "day05");
+        let sample_input = input_path(2022, SAMPLE_PROFILE, "day05");
+
+        // Assert
+        assert!(default_input.ends_with("inputs/2022/day05.input"));
+        assert!(other_answer.ends_with("inputs/2022/alice/day05.answer"));
+        assert!(sample_input.ends_with("samples/2022/day05.input"));
+    }
+
+    #[test]
+    fn test_profiles_start_with_default() -> anyhow::Result<()> {
+        // Act
+        let profiles = profiles(2022)?;
+
+        // Assert
+        assert_eq!(profiles.first().map(String::as_str), Some(DEFAULT_PROFILE));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_names_stay_inside_the_inputs() {
+        // Act
+        let names = ["alice", "../../etc", "alice/day05", "/etc", "..", ".", ""]
+            .map(|name| parse_name(name).is_ok());
+
+        // Assert
+        assert_eq!(names, [true, false, false, false, false, false, false]);
+    }
+
+    #[test]
+    fn test_profiles_reject_reserved_directories() -> anyhow::Result<()> {
+        // Arrange
+        let directory =
+            std::env::temp_dir().join(format!("aoc-profiles-reserved-{}", std::process::id()));
+        std::fs::create_dir_all(directory.join("alice"))?;
+        let allowed = profiles_in(&directory);
+        std::fs::create_dir_all(directory.join(SAMPLE_PROFILE))?;
+
+        // Act
+        let shadowed = profiles_in(&directory);
+        std::fs::remove_dir_all(&directory)?;
+
+        // Assert
+        assert_eq!(allowed?, vec![DEFAULT_PROFILE, SAMPLE_PROFILE, "alice"]);
+        assert!(shadowed.is_err());
+
+        Ok(())
+    }
+}
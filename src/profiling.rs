@@ -0,0 +1,317 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context as LayerContext;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting allocations and the heap memory in use.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record_allocation(size: usize) {
+        let size = u64::try_from(size).unwrap_or(u64::MAX);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE_BYTES
+            .fetch_add(size, Ordering::Relaxed)
+            .saturating_add(size);
+        PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_deallocation(size: usize) {
+        let size = u64::try_from(size).unwrap_or(u64::MAX);
+        LIVE_BYTES.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: Every call is forwarded unchanged to the system allocator, only counters are updated.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            Self::record_allocation(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            Self::record_allocation(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        Self::record_deallocation(layout.size());
+    }
+
+    /// Counts as freeing the old block and allocating one of the new size.
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            Self::record_deallocation(layout.size());
+            Self::record_allocation(new_size);
+        }
+        new_pointer
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Prefix of the lines the day binaries print to stderr for the runner.
+pub const STATS_PREFIX: &str = "aoc-stats";
+
+/// Time and memory one part of a day took.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PartStats {
+    pub part: u64,
+    pub elapsed: Duration,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+    /// Most heap memory in use at once during the part, including what was allocated before.
+    pub peak_bytes: u64,
+    /// Peak resident set size during the part, including what was resident before, where the
+    /// platform reports it and lets it be reset when the part starts.
+    pub max_resident_bytes: Option<u64>,
+}
+
+impl Display for PartStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{STATS_PREFIX} part={} nanos={} allocations={} allocated={} peak={}",
+            self.part,
+            self.elapsed.as_nanos(),
+            self.allocations,
+            self.allocated_bytes,
+            self.peak_bytes
+        )?;
+        if let Some(max_resident_bytes) = self.max_resident_bytes {
+            write!(f, " max_rss={max_resident_bytes}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PartStats {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s
+            .strip_prefix(STATS_PREFIX)
+            .ok_or_else(|| anyhow::anyhow!("Line does not start with \"{STATS_PREFIX}\"."))?
+            .split_whitespace()
+            .map(|field| {
+                let (key, value) = field
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("Field \"{field}\" has no value."))?;
+                let value = value
+                    .parse::<u64>()
+                    .with_context(|| format!("while parsing field \"{field}\""))?;
+                Ok((key, value))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|&&(key, _)| key == name)
+                .map(|&(_, value)| value)
+        };
+        let required =
+            |name: &str| field(name).ok_or_else(|| anyhow::anyhow!("Field \"{name}\" is missing."));
+        Ok(Self {
+            part: required("part")?,
+            elapsed: Duration::from_nanos(required("nanos")?),
+            allocations: required("allocations")?,
+            allocated_bytes: required("allocated")?,
+            peak_bytes: required("peak")?,
+            max_resident_bytes: field("max_rss"),
+        })
+    }
+}
+
+/// Collects the stats lines a day binary printed to stderr.
+pub fn parse_stats_lines(stderr: &str) -> anyhow::Result<Vec<PartStats>> {
+    stderr
+        .lines()
+        .filter(|line| line.starts_with(STATS_PREFIX))
+        .map(PartStats::from_str)
+        .collect()
+}
+
+/// Peak resident set size of this process, read from `/proc` on Linux.
+fn max_resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    kilobytes.checked_mul(1024)
+}
+
+/// Lowers the peak resident set size to the current one, by writing `5` to `/proc/self/clear_refs`
+/// on Linux. Returns whether it was reset.
+fn reset_max_resident_bytes() -> bool {
+    std::fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// Tracing layer printing the stats of every `part` span to stderr when it is exited.
+///
+/// Allocations are only counted with the `alloc-stats` feature, which installs the
+/// [`CountingAllocator`].
+pub struct PartStatsLayer;
+
+struct PartNumber(u64);
+
+struct PartStart {
+    instant: Instant,
+    is_resident_reset: bool,
+    allocations: u64,
+    allocated_bytes: u64,
+}
+
+struct PartNumberVisitor(Option<u64>);
+
+impl Visit for PartNumberVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "number" {
+            self.0 = Some(value);
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        if field.name() == "number" {
+            self.0 = u64::try_from(value).ok();
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+impl<S> Layer<S> for PartStatsLayer
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+        let mut visitor = PartNumberVisitor(None);
+        attributes.record(&mut visitor);
+        if let (Some(span), Some(number)) = (ctx.span(id), visitor.0) {
+            span.extensions_mut().insert(PartNumber(number));
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: LayerContext<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            PEAK_BYTES.store(LIVE_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+            let is_resident_reset = reset_max_resident_bytes();
+            span.extensions_mut().insert(PartStart {
+                instant: Instant::now(),
+                is_resident_reset,
+                allocations: ALLOCATIONS.load(Ordering::Relaxed),
+                allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+            });
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(start) = extensions.remove::<PartStart>() else {
+            return;
+        };
+        let Some(part) = extensions.get_mut::<PartNumber>().map(|number| number.0) else {
+            return;
+        };
+        let stats = PartStats {
+            part,
+            elapsed: start.instant.elapsed(),
+            allocations: ALLOCATIONS
+                .load(Ordering::Relaxed)
+                .saturating_sub(start.allocations),
+            allocated_bytes: ALLOCATED_BYTES
+                .load(Ordering::Relaxed)
+                .saturating_sub(start.allocated_bytes),
+            peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+            max_resident_bytes: start.is_resident_reset.then(max_resident_bytes).flatten(),
+        };
+        eprintln!("{stats}");
+    }
+}
+
+/// Bytes with a binary unit, e.g. `1.5 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    #[allow(clippy::cast_precision_loss, clippy::as_conversions)]
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in UNITS.into_iter().skip(1) {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{value:.1} {unit}")
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_stats_round_trip() -> anyhow::Result<()> {
+        // Arrange
+        let stats = PartStats {
+            part: 2,
+            elapsed: Duration::from_micros(1500),
+            allocations: 42,
+            allocated_bytes: 4096,
+            peak_bytes: 2048,
+            max_resident_bytes: Some(8_388_608),
+        };
+
+        // Act
+        let parsed = parse_stats_lines(&format!("WARN something\n{stats}\n"))?;
+
+        // Assert
+        assert_eq!(parsed, vec![stats]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        // Assert
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
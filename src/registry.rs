@@ -0,0 +1,147 @@
+/// A solution binary of this crate.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub binary: &'static str,
+    /// Whether the cargo feature of the day was enabled, i.e. the binary was built.
+    pub enabled: bool,
+}
+
+impl Solution {
+    /// Name of the input file, shared by all variants of a day (`day14-2` reads `day14`).
+    pub fn input_name(&self) -> String {
+        format!("day{:02}", self.day)
+    }
+}
+
+/// Every solution binary, ordered by year and day; a day may have several variants.
+///
+/// Binaries of 2022 are named `dayNN`, those of later years `yYYYY-dayNN`.
+#[rustfmt::skip]
+pub const SOLUTIONS: &[Solution] = &[
+    Solution { year: 2022, day: 1, binary: "day01", enabled: cfg!(feature = "day01") },
+    Solution { year: 2022, day: 2, binary: "day02", enabled: cfg!(feature = "day02") },
+    Solution { year: 2022, day: 3, binary: "day03", enabled: cfg!(feature = "day03") },
+    Solution { year: 2022, day: 4, binary: "day04", enabled: cfg!(feature = "day04") },
+    Solution { year: 2022, day: 5, binary: "day05", enabled: cfg!(feature = "day05") },
+    Solution { year: 2022, day: 6, binary: "day06", enabled: cfg!(feature = "day06") },
+    Solution { year: 2022, day: 7, binary: "day07", enabled: cfg!(feature = "day07") },
+    Solution { year: 2022, day: 8, binary: "day08", enabled: cfg!(feature = "day08") },
+    Solution { year: 2022, day: 9, binary: "day09", enabled: cfg!(feature = "day09") },
+    Solution { year: 2022, day: 10, binary: "day10", enabled: cfg!(feature = "day10") },
+    Solution { year: 2022, day: 11, binary: "day11", enabled: cfg!(feature = "day11") },
+    Solution { year: 2022, day: 12, binary: "day12", enabled: cfg!(feature = "day12") },
+    Solution { year: 2022, day: 13, binary: "day13", enabled: cfg!(feature = "day13") },
+    Solution { year: 2022, day: 14, binary: "day14", enabled: cfg!(feature = "day14") },
+    Solution { year: 2022, day: 14, binary: "day14-2", enabled: cfg!(feature = "day14") },
+];
+
+/// The first event of this crate, whose binaries keep their `dayNN` names.
+pub const FIRST_YEAR: u16 = 2022;
+
+/// Name of the binary and of the cargo feature of a day, following the scheme of [`SOLUTIONS`].
+pub fn binary_name(year: u16, day: u8) -> String {
+    if year == FIRST_YEAR {
+        format!("day{day:02}")
+    } else {
+        format!("y{year}-day{day:02}")
+    }
+}
+
+/// Puzzle titles by year and day, for reports.
+#[rustfmt::skip]
+pub const TITLES: &[(u16, u8, &str)] = &[
+    (2022, 1, "Calorie Counting"),
+    (2022, 2, "Rock Paper Scissors"),
+    (2022, 3, "Rucksack Reorganization"),
+    (2022, 4, "Camp Cleanup"),
+    (2022, 5, "Supply Stacks"),
+    (2022, 6, "Tuning Trouble"),
+    (2022, 7, "No Space Left On Device"),
+    (2022, 8, "Treetop Tree House"),
+    (2022, 9, "Rope Bridge"),
+    (2022, 10, "Cathode-Ray Tube"),
+    (2022, 11, "Monkey in the Middle"),
+    (2022, 12, "Hill Climbing Algorithm"),
+    (2022, 13, "Distress Signal"),
+    (2022, 14, "Regolith Reservoir"),
+];
+
+/// The title of the puzzle, if it is recorded in [`TITLES`].
+pub fn title(year: u16, day: u8) -> Option<&'static str> {
+    TITLES
+        .iter()
+        .find(|&&(title_year, title_day, _)| title_year == year && title_day == day)
+        .map(|&(_, _, title)| title)
+}
+
+/// The years with at least one solution, in ascending order.
+pub fn years() -> Vec<u16> {
+    let mut years = SOLUTIONS
+        .iter()
+        .map(|solution| solution.year)
+        .collect::<Vec<_>>();
+    years.dedup();
+    years
+}
+
+/// The solutions of a year.
+pub fn solutions(year: u16) -> impl Iterator<Item = &'static Solution> {
+    SOLUTIONS
+        .iter()
+        .filter(move |solution| solution.year == year)
+}
+
+/// The days of a year whose solutions were left out of this build by disabling their cargo
+/// feature.
+pub fn compiled_out_days(year: u16) -> Vec<u8> {
+    let mut days = solutions(year)
+        .filter(|solution| !solution.enabled)
+        .map(|solution| solution.day)
+        .collect::<Vec<_>>();
+    days.dedup();
+    days
+}
+
+/// The days of a year with at least one solution, in ascending order.
+pub fn implemented_days(year: u16) -> Vec<u8> {
+    let mut days = solutions(year)
+        .map(|solution| solution.day)
+        .collect::<Vec<_>>();
+    days.dedup();
+    days
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_implemented_days() {
+        // Act
+        let days = implemented_days(2022);
+
+        // Assert
+        assert!(days.windows(2).all(|pair| pair.first() < pair.last()));
+        assert!((1..=14).all(|day| days.contains(&day)));
+    }
+
+    #[test]
+    fn test_input_name() {
+        // Arrange
+        let variant = Solution {
+            year: 2022,
+            day: 14,
+            binary: "day14-2",
+            enabled: true,
+        };
+
+        // Act
+        let input_name = variant.input_name();
+
+        // Assert
+        assert_eq!(input_name, "day14");
+    }
+}
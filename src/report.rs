@@ -0,0 +1,285 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::journal::Journal;
+use crate::registry;
+use crate::runner::Run;
+
+/// Markup the report is written in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            _ => Err(anyhow::anyhow!(
+                "Format \"{s}\" is neither markdown nor html."
+            )),
+        }
+    }
+}
+
+impl Format {
+    fn begin(self, f: &mut std::fmt::Formatter<'_>, title: &str) -> std::fmt::Result {
+        match self {
+            Self::Markdown => Ok(()),
+            Self::Html => writeln!(
+                f,
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>",
+                escape_html(title)
+            ),
+        }
+    }
+
+    fn end(self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Markdown => Ok(()),
+            Self::Html => writeln!(f, "</body>\n</html>"),
+        }
+    }
+
+    fn heading(
+        self,
+        f: &mut std::fmt::Formatter<'_>,
+        level: usize,
+        text: &str,
+    ) -> std::fmt::Result {
+        match self {
+            Self::Markdown => writeln!(f, "{} {text}\n", "#".repeat(level)),
+            Self::Html => writeln!(f, "<h{level}>{}</h{level}>", escape_html(text)),
+        }
+    }
+
+    /// A bullet list; items are plain text with inline code in backticks.
+    fn list(self, f: &mut std::fmt::Formatter<'_>, items: &[String]) -> std::fmt::Result {
+        if items.is_empty() {
+            return Ok(());
+        }
+        match self {
+            Self::Markdown => {
+                for item in items {
+                    writeln!(f, "- {item}")?;
+                }
+                writeln!(f)
+            }
+            Self::Html => {
+                writeln!(f, "<ul>")?;
+                for item in items {
+                    writeln!(f, "<li>{}</li>", inline_code_to_html(item))?;
+                }
+                writeln!(f, "</ul>")
+            }
+        }
+    }
+
+    fn preformatted(self, f: &mut std::fmt::Formatter<'_>, text: &str) -> std::fmt::Result {
+        match self {
+            Self::Markdown => writeln!(f, "```text\n{}\n```\n", text.trim_end()),
+            Self::Html => writeln!(f, "<pre>{}</pre>", escape_html(text.trim_end())),
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes the text and turns the parts in backticks into `<code>` elements.
+fn inline_code_to_html(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(index, part)| {
+            if index % 2 == 1 {
+                format!("<code>{}</code>", escape_html(part))
+            } else {
+                escape_html(part)
+            }
+        })
+        .collect()
+}
+
+/// One document with the answers, timings, solve times and renders of every day that was run.
+pub struct Report<'runs> {
+    pub runs: &'runs [Run],
+    /// Solve times of the puzzles.
+    pub journal: &'runs Journal,
+    pub format: Format,
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = self.format;
+        format.begin(f, "Advent of Code")?;
+        format.heading(f, 1, "Advent of Code")?;
+        let mut previous: Option<(u16, u8)> = None;
+        for run in self.runs {
+            let solution = run.solution;
+            if previous.map(|(year, _)| year) != Some(solution.year) {
+                format.heading(f, 2, &solution.year.to_string())?;
+            }
+            if previous != Some((solution.year, solution.day)) {
+                let heading = match registry::title(solution.year, solution.day) {
+                    Some(title) => format!("Day {}: {title}", solution.day),
+                    None => format!("Day {}", solution.day),
+                };
+                format.heading(f, 3, &heading)?;
+                format.list(f, &self.solve_times(solution.year, solution.day))?;
+            }
+            previous = Some((solution.year, solution.day));
+            self.write_run(f, run)?;
+        }
+        format.end(f)
+    }
+}
+
+impl Report<'_> {
+    fn solve_times(&self, year: u16, day: u8) -> Vec<String> {
+        self.journal
+            .entries
+            .iter()
+            .filter(|entry| entry.year == year && entry.day == day)
+            .map(|entry| {
+                let line = format!(
+                    "Part {} solved in {} (attempt {})",
+                    entry.part, entry.duration, entry.attempt
+                );
+                match entry.note {
+                    Some(ref note) => format!("{line}: {note}"),
+                    None => line,
+                }
+            })
+            .collect()
+    }
+
+    fn write_run(&self, f: &mut std::fmt::Formatter<'_>, run: &Run) -> std::fmt::Result {
+        let format = self.format;
+        let elapsed = run
+            .elapsed
+            .map_or_else(String::new, |elapsed| format!(" in {elapsed:.1?}"));
+        format.heading(
+            f,
+            4,
+            &format!(
+                "{} ({}): {}{elapsed}",
+                run.solution.binary,
+                run.profile,
+                run.outcome.status()
+            ),
+        )?;
+        let timings = run
+            .parts
+            .iter()
+            .map(|part| format!("Part {} ran in {:.1?}", part.part, part.elapsed))
+            .collect::<Vec<_>>();
+        format.list(f, &timings)?;
+        let answers = run
+            .outcome
+            .actual()
+            .map(crate::sample::answers_in_order)
+            .unwrap_or_default();
+        let (single_line, multi_line): (Vec<_>, Vec<_>) = answers
+            .into_iter()
+            .partition(|(_, answer)| !answer.contains('\n'));
+        let single_line = single_line
+            .into_iter()
+            .map(|(label, answer)| format!("`{label}`: `{answer}`"))
+            .collect::<Vec<_>>();
+        format.list(f, &single_line)?;
+        // Images like the day 10 CRT are answers and renders at once, so they are shown once.
+        for (label, answer) in multi_line.into_iter().filter(|(_, answer)| {
+            !run.renders
+                .iter()
+                .any(|(_, render)| render.trim_end() == answer.trim_end())
+        }) {
+            format.heading(f, 5, &label)?;
+            format.preformatted(f, &answer)?;
+        }
+        for (name, render) in &run.renders {
+            format.heading(f, 5, &format!("Render: {name}"))?;
+            format.preformatted(f, render)?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::registry::Solution;
+    use crate::runner::Outcome;
+
+    fn day10_run() -> Run {
+        Run {
+            solution: Solution {
+                year: 2022,
+                day: 10,
+                binary: "day10",
+                enabled: true,
+            },
+            profile: "default".to_owned(),
+            outcome: Outcome::Correct {
+                actual: "part_1_solution: 13140\npart_2_solution:\n##..\n#..#\n".to_owned(),
+            },
+            elapsed: None,
+            parts: Vec::new(),
+            renders: vec![("crt".to_owned(), "##..\n#..#".to_owned())],
+        }
+    }
+
+    #[test]
+    fn test_markdown_report() -> anyhow::Result<()> {
+        // Arrange
+        let runs = [day10_run()];
+        let journal = Journal::from_str("2022 10 1 1 1h39m43s")?;
+
+        // Act
+        let report = Report {
+            runs: &runs,
+            journal: &journal,
+            format: Format::Markdown,
+        }
+        .to_string();
+
+        // Assert
+        assert!(report.contains("### Day 10: Cathode-Ray Tube\n"));
+        assert!(report.contains("- Part 1 solved in 1h39m43s (attempt 1)\n"));
+        assert!(report.contains("- `part_1_solution`: `13140`\n"));
+        assert!(report.contains("##### Render: crt\n\n```text\n##..\n#..#\n```"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_html_report_escapes_text() -> anyhow::Result<()> {
+        // Arrange
+        let mut run = day10_run();
+        run.renders = vec![("tree".to_owned(), "- / (dir)\n  - a <b>".to_owned())];
+        let runs = [run];
+
+        // Act
+        let report = Report {
+            runs: &runs,
+            journal: &Journal::default(),
+            format: Format::Html,
+        }
+        .to_string();
+
+        // Assert
+        assert!(report.contains("<li><code>part_1_solution</code>: <code>13140</code></li>"));
+        assert!(report.contains("<pre>- / (dir)\n  - a &lt;b&gt;</pre>"));
+
+        Ok(())
+    }
+}
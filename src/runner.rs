@@ -0,0 +1,413 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::profiling::PartStats;
+use crate::registry::Solution;
+use crate::{profile, profiling, sample};
+
+/// Directory of the solution binaries, which are built next to the runner itself.
+pub fn binary_dir() -> anyhow::Result<PathBuf> {
+    let runner = std::env::current_exe().context("while locating the runner binary")?;
+    runner
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| anyhow::anyhow!("Runner {} has no parent directory.", runner.display()))
+}
+
+/// One solution binary run against the input of one profile.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Run {
+    pub solution: Solution,
+    pub profile: String,
+    pub outcome: Outcome,
+    /// How long the binary ran, if it was started at all.
+    pub elapsed: Option<Duration>,
+    /// Time and memory of each part, reported by binaries built with the `alloc-stats` feature.
+    pub parts: Vec<PartStats>,
+    /// Names and contents of the renders the binary wrote, if a render root was given.
+    pub renders: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    /// The output equals the recorded answer.
+    Correct { actual: String },
+    /// The output differs from the recorded answer.
+    Wrong { expected: String, actual: String },
+    /// There is no recorded answer to compare the output with.
+    Unchecked { actual: String },
+    /// The profile has no input for the day.
+    MissingInput,
+    /// The cargo feature of the day was disabled, so there is no binary to run.
+    CompiledOut,
+    /// The binary could not be started or exited unsuccessfully.
+    Failed { error: String },
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Wrong { .. } | Self::Failed { .. })
+    }
+
+    /// Short status shown in reports.
+    pub fn status(&self) -> &'static str {
+        match *self {
+            Self::Correct { .. } => "ok",
+            Self::Wrong { .. } => "WRONG",
+            Self::Unchecked { .. } => "unchecked",
+            Self::MissingInput => "no input",
+            Self::CompiledOut => "compiled out",
+            Self::Failed { .. } => "FAILED",
+        }
+    }
+
+    /// The output of the binary, if it ran successfully.
+    pub fn actual(&self) -> Option<&str> {
+        match *self {
+            Self::Correct { ref actual }
+            | Self::Wrong { ref actual, .. }
+            | Self::Unchecked { ref actual } => Some(actual),
+            Self::MissingInput | Self::CompiledOut | Self::Failed { .. } => None,
+        }
+    }
+}
+
+impl Display for Run {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:<8} {:<10} {}",
+            self.solution.year,
+            self.solution.binary,
+            self.profile,
+            self.outcome.status()
+        )?;
+        match self.outcome {
+            Outcome::Wrong {
+                ref expected,
+                ref actual,
+            } => write!(
+                f,
+                "\n  expected:\n{}\n  actual:\n{}",
+                indent(expected),
+                indent(actual)
+            ),
+            Outcome::Failed { ref error } => write!(f, "\n{}", indent(error)),
+            Outcome::Correct { .. }
+            | Outcome::Unchecked { .. }
+            | Outcome::MissingInput
+            | Outcome::CompiledOut => Ok(()),
+        }
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("    {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Table of every run with its answers and timing, followed by the totals.
+pub struct Summary<'runs> {
+    pub runs: &'runs [Run],
+    /// Wall time of all runs together, which is less than their sum when run in parallel.
+    pub elapsed: Duration,
+}
+
+impl Display for Summary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<4} {:<8} {:<10} {:<12} {:>10}  answers",
+            "year", "binary", "profile", "status", "time"
+        )?;
+        for run in self.runs {
+            let elapsed = run
+                .elapsed
+                .map_or_else(|| "-".to_owned(), |elapsed| format!("{elapsed:.1?}"));
+            writeln!(
+                f,
+                "{:<4} {:<8} {:<10} {:<12} {elapsed:>10}  {}",
+                run.solution.year,
+                run.solution.binary,
+                run.profile,
+                run.outcome.status(),
+                run.outcome
+                    .actual()
+                    .map(summarize_answers)
+                    .unwrap_or_default()
+            )?;
+            for part in &run.parts {
+                writeln!(
+                    f,
+                    "{:<25}{:<12} {:>10}  {}",
+                    "",
+                    format!("part {}", part.part),
+                    format!("{:.1?}", part.elapsed),
+                    summarize_memory(part)
+                )?;
+            }
+        }
+        let mut counts = BTreeMap::new();
+        for run in self.runs {
+            let count: &mut usize = counts.entry(run.outcome.status()).or_default();
+            *count = count.saturating_add(1);
+        }
+        let counts = counts
+            .into_iter()
+            .map(|(status, count)| format!("{count} {status}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let total = self
+            .runs
+            .iter()
+            .filter_map(|run| run.elapsed)
+            .sum::<Duration>();
+        write!(
+            f,
+            "{} runs: {counts} in {:.1?} ({total:.1?} summed)",
+            self.runs.len(),
+            self.elapsed
+        )
+    }
+}
+
+/// The answers of an output on one line, with multi-line answers like images shortened.
+fn summarize_answers(output: &str) -> String {
+    sample::answers_in_order(output)
+        .into_iter()
+        .map(|(label, answer)| {
+            let line_count = answer.lines().count();
+            if line_count > 1 {
+                format!("{label}=<{line_count} lines>")
+            } else {
+                format!("{label}={answer}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The memory use of a part on one line.
+fn summarize_memory(part: &PartStats) -> String {
+    let max_resident = part
+        .max_resident_bytes
+        .map_or_else(|| "-".to_owned(), profiling::format_bytes);
+    format!(
+        "{} allocations, {} allocated, {} peak heap, {max_resident} max RSS",
+        part.allocations,
+        profiling::format_bytes(part.allocated_bytes),
+        profiling::format_bytes(part.peak_bytes)
+    )
+}
+
+/// Runs every solution against every profile, in parallel with the `parallel` feature.
+///
+/// The runs are returned in the order of the solutions and profiles, and a failing run does not
+/// stop the others. With a render root, the binaries write their renders below it.
+pub fn run_all(
+    binary_dir: &Path,
+    solutions: &[Solution],
+    profiles: &[String],
+    render_root: Option<&Path>,
+) -> Vec<Run> {
+    let jobs = solutions
+        .iter()
+        .flat_map(|solution| profiles.iter().map(move |profile| (solution, profile)))
+        .collect::<Vec<_>>();
+    #[cfg(feature = "parallel")]
+    let jobs = jobs.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let jobs = jobs.into_iter();
+    jobs.map(|(solution, profile)| run(binary_dir, solution, profile, render_root))
+        .collect()
+}
+
+#[tracing::instrument(skip_all, fields(year = solution.year, binary = solution.binary, profile = profile))]
+pub fn run(
+    binary_dir: &Path,
+    solution: &Solution,
+    profile: &str,
+    render_root: Option<&Path>,
+) -> Run {
+    let render_dir = render_root.map(|render_root| {
+        render_root
+            .join(solution.year.to_string())
+            .join(solution.binary)
+            .join(profile)
+    });
+    let mut elapsed = None;
+    let mut parts = Vec::new();
+    let mut renders = Vec::new();
+    let outcome = run_outcome(
+        binary_dir,
+        solution,
+        profile,
+        render_dir.as_deref(),
+        &mut elapsed,
+        &mut parts,
+    )
+    .and_then(|outcome| {
+        if let Some(ref render_dir) = render_dir {
+            renders = read_renders(render_dir)?;
+        }
+        Ok(outcome)
+    })
+    .unwrap_or_else(|error| Outcome::Failed {
+        error: format!("{error:?}"),
+    });
+    tracing::debug!(?outcome, ?elapsed, "run finished");
+    Run {
+        solution: *solution,
+        profile: profile.to_owned(),
+        outcome,
+        elapsed,
+        parts,
+        renders,
+    }
+}
+
+/// The renders in the directory, sorted by name.
+fn read_renders(render_dir: &Path) -> anyhow::Result<Vec<(String, String)>> {
+    if !render_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut renders = std::fs::read_dir(render_dir)
+        .with_context(|| format!("while listing {}", render_dir.display()))?
+        .map(|entry| {
+            let path = entry
+                .with_context(|| format!("while listing {}", render_dir.display()))?
+                .path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|file_name| file_name.to_str()?.strip_suffix(".txt"))
+            else {
+                return Ok(None);
+            };
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("while reading render {}", path.display()))?;
+            Ok(Some((name.to_owned(), content)))
+        })
+        .filter_map(Result::transpose)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    renders.sort_unstable();
+    Ok(renders)
+}
+
+fn run_outcome(
+    binary_dir: &Path,
+    solution: &Solution,
+    profile: &str,
+    render_dir: Option<&Path>,
+    elapsed: &mut Option<Duration>,
+    parts: &mut Vec<PartStats>,
+) -> anyhow::Result<Outcome> {
+    if !solution.enabled {
+        return Ok(Outcome::CompiledOut);
+    }
+    if !profile::has_input(solution.year, profile, &solution.input_name()) {
+        return Ok(Outcome::MissingInput);
+    }
+    let binary = binary_dir.join(format!(
+        "{}{}",
+        solution.binary,
+        std::env::consts::EXE_SUFFIX
+    ));
+    let mut command = Command::new(&binary);
+    command.arg("--profile").arg(profile);
+    if let Some(render_dir) = render_dir {
+        command.arg("--render-dir").arg(render_dir);
+    }
+    let start = Instant::now();
+    let output = command.output().with_context(|| {
+        format!(
+            "while starting {} (build it with `cargo build --release --bins`)",
+            binary.display()
+        )
+    })?;
+    *elapsed = Some(start.elapsed());
+    if !output.status.success() {
+        return Ok(Outcome::Failed {
+            error: format!(
+                "{} exited with {}\n{}",
+                solution.binary,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            ),
+        });
+    }
+    *parts = profiling::parse_stats_lines(&String::from_utf8_lossy(&output.stderr))
+        .with_context(|| format!("while reading the part stats of {}", solution.binary))?;
+    let actual = String::from_utf8(output.stdout)
+        .with_context(|| format!("while reading the output of {}", solution.binary))?;
+    Ok(
+        match profile::load_answer(solution.year, profile, &solution.input_name())? {
+            None => Outcome::Unchecked { actual },
+            Some(expected) if expected.trim_end() == actual.trim_end() => {
+                Outcome::Correct { actual }
+            }
+            Some(expected) => Outcome::Wrong { expected, actual },
+        },
+    )
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_all_keeps_going_after_failures() {
+        // Arrange
+        let solutions = [
+            Solution {
+                year: 2022,
+                day: 1,
+                binary: "day01",
+                enabled: true,
+            },
+            Solution {
+                year: 2022,
+                day: 2,
+                binary: "day02",
+                enabled: false,
+            },
+        ];
+
+        // Act
+        let runs = run_all(
+            Path::new("/nonexistent"),
+            &solutions,
+            &[profile::SAMPLE_PROFILE.to_owned()],
+            None,
+        );
+
+        // Assert
+        let statuses = runs
+            .iter()
+            .map(|run| (run.solution.binary, run.outcome.status()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![("day01", "FAILED"), ("day02", "compiled out")]
+        );
+    }
+
+    #[test]
+    fn test_summarize_answers() {
+        // Act
+        let summary = summarize_answers("part_1_solution: 13140\npart_2_solution:\n##..\n#..#\n");
+
+        // Assert
+        assert_eq!(summary, "part_1_solution=13140 part_2_solution=<2 lines>");
+    }
+}
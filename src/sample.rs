@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+/// Splits the output of a day binary into its answers, keyed by their label.
+///
+/// A line like `part_1_solution: 42` starts an answer; lines without a label continue the
+/// previous one, which is how multi-line answers like the CRT image of day 10 are printed.
+pub fn parse_answers(output: &str) -> BTreeMap<String, String> {
+    answers_in_order(output).into_iter().collect()
+}
+
+/// The labeled answers in the order they were printed.
+pub fn answers_in_order(output: &str) -> Vec<(String, String)> {
+    let mut answers: Vec<(String, Vec<&str>)> = Vec::new();
+    for line in output.lines() {
+        match line.split_once(':') {
+            Some((label, value)) if is_label(label) => {
+                let value = value.trim();
+                let lines = if value.is_empty() {
+                    vec![]
+                } else {
+                    vec![value]
+                };
+                answers.push((label.to_owned(), lines));
+            }
+            _ => {
+                if let Some(&mut (_, ref mut lines)) = answers.last_mut() {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    answers
+        .into_iter()
+        .map(|(label, lines)| (label, lines.join("\n")))
+        .collect()
+}
+
+fn is_label(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+/// Generates a test per part that solves every listed sample of `samples/<year>/` and compares the
+/// result with the answer of the same label in the sample's `.answer` file.
+///
+/// Samples without an answer for a label are skipped for that part. Attributes like `#[ignore]`
+/// in front of a test are put on the generated test.
+///
+/// ```ignore
+/// aoc_2022::sample_tests! {
+///     year: 2022,
+///     samples: ["day06", "day06-2"],
+///     test_part_1_default: "part_1_solution" => |input| solve_part_1(input),
+///     test_part_2_default: "part_2_solution" => |input| solve_part_2(input),
+/// }
+/// ```
+#[macro_export]
+macro_rules! sample_tests {
+    (@samples $year:literal [$($sample:literal),+ $(,)?]) => {
+        [$((
+            $sample,
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/samples/", $year, "/", $sample, ".input")),
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/samples/", $year, "/", $sample, ".answer")),
+        )),+]
+    };
+    (
+        year: $year:literal,
+        samples: $samples:tt,
+        $($(#[$attribute:meta])* $test:ident: $label:literal => $solve:expr),+ $(,)?
+    ) => {
+        $(
+            $(#[$attribute])*
+            #[test]
+            fn $test() -> anyhow::Result<()> {
+                let samples = $crate::sample_tests!(@samples $year $samples);
+                let solve: fn(&str) -> anyhow::Result<_> = $solve;
+                let mut checked = 0_usize;
+                for (sample, input, answer) in samples {
+                    let answers = $crate::sample::parse_answers(answer);
+                    let Some(expected) = answers.get($label) else {
+                        continue;
+                    };
+                    let actual = solve(input)?.to_string();
+                    assert_eq!(&actual, expected, "{} of sample {}", $label, sample);
+                    checked = checked.saturating_add(1);
+                }
+                if checked == 0 {
+                    return Err(anyhow::anyhow!("No sample has an answer for {}.", $label));
+                }
+                Ok(())
+            }
+        )+
+    };
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        // Arrange
+        let output = "part_1_solution: 13140\npart_2_solution:\n##..\n#..#\n";
+
+        // Act
+        let answers = parse_answers(output);
+
+        // Assert
+        assert_eq!(
+            answers,
+            BTreeMap::from([
+                ("part_1_solution".to_owned(), "13140".to_owned()),
+                ("part_2_solution".to_owned(), "##..\n#..#".to_owned()),
+            ])
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::registry;
+
+const DAY_TEMPLATE: &str = include_str!("../templates/day.rs.template");
+
+/// Location of the repository the `new-day` command scaffolds into.
+pub fn default_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Generates the module, binary entry, feature, input and sample slots and registry entry of a new
+/// day.
+///
+/// Returns the paths of all created or changed files.
+pub fn new_day(root: &Path, year: u16, day: u8) -> anyhow::Result<Vec<PathBuf>> {
+    if year < 2015 {
+        return Err(anyhow::anyhow!(
+            "Year {year} is before the first Advent of Code in 2015."
+        ));
+    }
+    if !(1..=25).contains(&day) {
+        return Err(anyhow::anyhow!("Day {day} is not between 1 and 25."));
+    }
+    let module_path = root.join(format!("src/{}.rs", registry::binary_name(year, day)));
+    let input_path = root.join(format!("inputs/{year}/day{day:02}.input"));
+    let sample_input_path = root.join(format!("samples/{year}/day{day:02}.input"));
+    let sample_answer_path = sample_input_path.with_extension("answer");
+    let manifest_path = root.join("Cargo.toml");
+    let registry_path = root.join("src/registry.rs");
+    for path in [
+        &module_path,
+        &input_path,
+        &sample_input_path,
+        &sample_answer_path,
+    ] {
+        if path.exists() {
+            return Err(anyhow::anyhow!("{} does already exist.", path.display()));
+        }
+    }
+
+    // Compute every change before writing, so that a failure leaves the tree untouched.
+    let manifest = add_feature(
+        &add_bin_entry(&read(&manifest_path)?, year, day)?,
+        year,
+        day,
+    )?;
+    let registry = add_registry_entry(&read(&registry_path)?, year, day)?;
+
+    for path in [&input_path, &sample_input_path] {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("while creating {}", dir.display()))?;
+        }
+    }
+    write(&module_path, &render_day(year, day))?;
+    write(&input_path, "")?;
+    write(&sample_input_path, "")?;
+    write(&sample_answer_path, "")?;
+    write(&manifest_path, &manifest)?;
+    write(&registry_path, &registry)?;
+    Ok(vec![
+        module_path,
+        input_path,
+        sample_input_path,
+        sample_answer_path,
+        manifest_path,
+        registry_path,
+    ])
+}
+
+fn read(path: &Path) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("while reading {}", path.display()))
+}
+
+fn write(path: &Path, content: &str) -> anyhow::Result<()> {
+    std::fs::write(path, content).with_context(|| format!("while writing {}", path.display()))
+}
+
+/// The year and day of a binary or feature named like [`registry::binary_name`], ignoring the
+/// suffix of variants like `day14-2`.
+fn key_of_binary(name: &str) -> Option<(u16, u8)> {
+    let (year, day) = match name.strip_prefix('y') {
+        Some(rest) => {
+            let (year, day) = rest.split_once("-day")?;
+            (year.parse().ok()?, day)
+        }
+        None => (registry::FIRST_YEAR, name.strip_prefix("day")?),
+    };
+    Some((year, day.get(..2)?.parse().ok()?))
+}
+
+/// Source of a new day module.
+pub fn render_day(year: u16, day: u8) -> String {
+    DAY_TEMPLATE
+        .replace("{{YEAR}}", &year.to_string())
+        .replace("{{DAY}}", &format!("{day:02}"))
+}
+
+/// Inserts a `[[bin]]` block for the day after the last binary of an earlier day.
+pub fn add_bin_entry(manifest: &str, year: u16, day: u8) -> anyhow::Result<String> {
+    let name = registry::binary_name(year, day);
+    let mut blocks = manifest
+        .split("\n\n")
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    let key_of_block = |block: &str| -> Option<(u16, u8)> {
+        let path = block
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("path = \"src/"))?;
+        key_of_binary(path.strip_suffix(".rs\"")?)
+    };
+    if blocks
+        .iter()
+        .any(|block| block.starts_with("[[bin]]") && key_of_block(block) == Some((year, day)))
+    {
+        return Err(anyhow::anyhow!("Cargo.toml does already contain {name}."));
+    }
+    let insert_after = blocks
+        .iter()
+        .rposition(|block| {
+            block.starts_with("[[bin]]")
+                && key_of_block(block).is_some_and(|other| other < (year, day))
+        })
+        .or_else(|| blocks.iter().position(|block| block.starts_with("[[bin]]")))
+        .ok_or_else(|| anyhow::anyhow!("Cargo.toml does not contain any [[bin]]."))?;
+    blocks.insert(
+        insert_after.saturating_add(1),
+        format!("[[bin]]\npath = \"src/{name}.rs\"\nname = \"{name}\"\nrequired-features = [\"{name}\"]"),
+    );
+    Ok(blocks.join("\n\n"))
+}
+
+/// Adds the cargo feature of the day and enables it in `all-days`.
+pub fn add_feature(manifest: &str, year: u16, day: u8) -> anyhow::Result<String> {
+    let name = registry::binary_name(year, day);
+    let mut lines = manifest.lines().map(str::to_owned).collect::<Vec<_>>();
+    let key_of_feature = |line: &str| key_of_binary(line.split_once(" = ")?.0);
+    let key_of_enabled =
+        |line: &str| key_of_binary(line.trim().strip_prefix('"')?.strip_suffix("\",")?);
+    if lines
+        .iter()
+        .any(|line| key_of_feature(line) == Some((year, day)))
+    {
+        return Err(anyhow::anyhow!(
+            "Cargo.toml does already contain feature {name}."
+        ));
+    }
+
+    let feature_at = lines
+        .iter()
+        .rposition(|line| key_of_feature(line).is_some_and(|other| other < (year, day)))
+        .or_else(|| {
+            lines
+                .iter()
+                .position(|line| line.starts_with("all-days = ["))
+                .and_then(|all_days| {
+                    lines
+                        .iter()
+                        .skip(all_days)
+                        .position(|line| line == "]")
+                        .and_then(|offset| all_days.checked_add(offset))
+                })
+        })
+        .ok_or_else(|| anyhow::anyhow!("Cargo.toml has no day features."))?;
+    lines.insert(feature_at.saturating_add(1), format!("{name} = []"));
+
+    let all_days = lines
+        .iter()
+        .position(|line| line.starts_with("all-days = ["))
+        .ok_or_else(|| anyhow::anyhow!("Cargo.toml has no all-days feature."))?;
+    let all_days_end = lines
+        .iter()
+        .skip(all_days)
+        .position(|line| line == "]")
+        .and_then(|offset| all_days.checked_add(offset))
+        .ok_or_else(|| anyhow::anyhow!("The all-days feature is not terminated."))?;
+    let enable_at = lines
+        .get(all_days..all_days_end)
+        .unwrap_or_default()
+        .iter()
+        .position(|line| key_of_enabled(line).is_some_and(|other| other > (year, day)))
+        .and_then(|offset| all_days.checked_add(offset))
+        .unwrap_or(all_days_end);
+    lines.insert(enable_at, format!("    \"{name}\","));
+
+    let mut manifest = lines.join("\n");
+    manifest.push('\n');
+    Ok(manifest)
+}
+
+/// Inserts the day into `SOLUTIONS` of the registry, keeping the list ordered by year and day.
+pub fn add_registry_entry(registry: &str, year: u16, day: u8) -> anyhow::Result<String> {
+    let name = registry::binary_name(year, day);
+    let entry = format!(
+        "    Solution {{ year: {year}, day: {day}, binary: \"{name}\", enabled: cfg!(feature = \"{name}\") }},"
+    );
+    let mut lines = registry.lines().collect::<Vec<_>>();
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const SOLUTIONS"))
+        .ok_or_else(|| anyhow::anyhow!("Registry does not contain SOLUTIONS."))?;
+    let end = lines
+        .iter()
+        .skip(start)
+        .position(|line| *line == "];")
+        .and_then(|offset| start.checked_add(offset))
+        .ok_or_else(|| anyhow::anyhow!("SOLUTIONS of the registry are not terminated."))?;
+    let key_of_line = |line: &str| -> Option<(u16, u8)> {
+        let (year, rest) = line
+            .trim()
+            .strip_prefix("Solution { year: ")?
+            .split_once(", day: ")?;
+        Some((year.parse().ok()?, rest.split(',').next()?.parse().ok()?))
+    };
+    let solutions = lines.get(start..end).unwrap_or_default();
+    if solutions
+        .iter()
+        .any(|line| key_of_line(line) == Some((year, day)))
+    {
+        return Err(anyhow::anyhow!(
+            "Day {day} of {year} is already registered."
+        ));
+    }
+    let insert_at = solutions
+        .iter()
+        .position(|line| key_of_line(line).is_some_and(|other| other > (year, day)))
+        .and_then(|offset| start.checked_add(offset))
+        .unwrap_or(end);
+    lines.insert(insert_at, &entry);
+    let mut registry = lines.join("\n");
+    registry.push('\n');
+    Ok(registry)
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_day() {
+        // Act
+        let source = render_day(2022, 7);
+
+        // Assert
+        assert!(source.contains("args.input(2022, \"day07\")"));
+        assert!(source.contains("aoc_2022::sample_tests! {"));
+        assert!(source.contains("samples: [\"day07\"]"));
+        assert!(!source.contains("TEST_INPUT"));
+        assert!(!source.contains("{{"));
+    }
+
+    #[test]
+    fn test_add_bin_entry() -> anyhow::Result<()> {
+        // Arrange
+        let manifest = "[package]\nname = \"aoc-2022\"\n\n[[bin]]\npath = \"src/day01.rs\"\nname = \"day01\"\nrequired-features = [\"day01\"]\n\n[[bin]]\npath = \"src/bin/aoc.rs\"\nname = \"aoc\"\n\n[dependencies]\nanyhow = \"1\"\n";
+
+        // Act
+        let manifest = add_bin_entry(manifest, 2022, 15)?;
+        let manifest = add_bin_entry(&manifest, 2023, 1)?;
+
+        // Assert
+        assert_eq!(
+            manifest,
+            "[package]\nname = \"aoc-2022\"\n\n[[bin]]\npath = \"src/day01.rs\"\nname = \"day01\"\nrequired-features = [\"day01\"]\n\n[[bin]]\npath = \"src/day15.rs\"\nname = \"day15\"\nrequired-features = [\"day15\"]\n\n[[bin]]\npath = \"src/y2023-day01.rs\"\nname = \"y2023-day01\"\nrequired-features = [\"y2023-day01\"]\n\n[[bin]]\npath = \"src/bin/aoc.rs\"\nname = \"aoc\"\n\n[dependencies]\nanyhow = \"1\"\n"
+        );
+        assert!(add_bin_entry(&manifest, 2022, 15).is_err());
+        assert!(add_bin_entry(&manifest, 2023, 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_feature() -> anyhow::Result<()> {
+        // Arrange
+        let manifest = "[features]\ndefault = [\"all-days\"]\nall-days = [\n    \"day01\",\n    \"day03\",\n]\nday01 = []\nday03 = [\"dep:regex\"]\n\n[dependencies]\n";
+
+        // Act
+        let manifest = add_feature(manifest, 2023, 1)?;
+        let manifest = add_feature(&manifest, 2022, 2)?;
+
+        // Assert
+        assert_eq!(
+            manifest,
+            "[features]\ndefault = [\"all-days\"]\nall-days = [\n    \"day01\",\n    \"day02\",\n    \"day03\",\n    \"y2023-day01\",\n]\nday01 = []\nday02 = []\nday03 = [\"dep:regex\"]\ny2023-day01 = []\n\n[dependencies]\n"
+        );
+        assert!(add_feature(&manifest, 2022, 2).is_err());
+        assert!(add_feature(&manifest, 2023, 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_registry_entry() -> anyhow::Result<()> {
+        // Arrange
+        let registry = include_str!("registry.rs");
+
+        // Act
+        let registry = add_registry_entry(registry, 2022, 15)?;
+        let registry = add_registry_entry(&registry, 2023, 1)?;
+
+        // Assert
+        assert!(registry.contains(
+            "    Solution { year: 2022, day: 15, binary: \"day15\", enabled: cfg!(feature = \"day15\") },\n    Solution { year: 2023, day: 1, binary: \"y2023-day01\", enabled: cfg!(feature = \"y2023-day01\") },\n];"
+        ));
+        assert!(add_registry_entry(&registry, 2022, 1).is_err());
+
+        Ok(())
+    }
+}
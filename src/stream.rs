@@ -0,0 +1,78 @@
+use std::io::BufRead;
+
+use anyhow::Context;
+
+/// Lines of a reader, with the line number in read errors.
+pub struct Lines<R> {
+    lines: std::io::Lines<R>,
+    index: usize,
+}
+
+pub fn lines<R: BufRead>(reader: R) -> Lines<R> {
+    Lines {
+        lines: reader.lines(),
+        index: 0,
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = anyhow::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index;
+        self.index = self.index.saturating_add(1);
+        self.lines
+            .next()
+            .map(|line| line.with_context(|| format!("while reading line #{index}")))
+    }
+}
+
+/// Groups of lines separated by blank lines, holding only one group in memory at a time.
+pub struct Blocks<R> {
+    lines: Lines<R>,
+}
+
+pub fn blocks<R: BufRead>(reader: R) -> Blocks<R> {
+    Blocks {
+        lines: lines(reader),
+    }
+}
+
+impl<R: BufRead> Iterator for Blocks<R> {
+    type Item = anyhow::Result<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block = Vec::new();
+        for line in self.lines.by_ref() {
+            match line {
+                Err(error) => return Some(Err(error)),
+                Ok(line) if line.is_empty() => return Some(Ok(block)),
+                Ok(line) => block.push(line),
+            }
+        }
+        (!block.is_empty()).then_some(Ok(block))
+    }
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks() -> anyhow::Result<()> {
+        // Arrange
+        let input = "1000\n2000\n\n3000\n\n4000\n";
+
+        // Act
+        let blocks = blocks(input.as_bytes()).collect::<anyhow::Result<Vec<_>>>()?;
+
+        // Assert
+        assert_eq!(
+            blocks,
+            vec![vec!["1000", "2000"], vec!["3000"], vec!["4000"]]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,347 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+/// Environment variable holding the hex encoded vault key, preferred over the keyfile.
+pub const KEY_ENV_VAR: &str = "AOC_VAULT_KEY";
+
+/// File extension of encrypted inputs, appended to the plain file name.
+pub const EXTENSION: &str = "vault";
+
+const HEADER: &str = "aoc-vault v1";
+
+/// Location of the local keyfile, which must never be committed.
+pub fn default_key_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(".vault-key")
+}
+
+/// Path of the encrypted counterpart of a plain file, e.g. `day05.input.vault`.
+pub fn vault_path(plain_path: &Path) -> PathBuf {
+    let mut file_name = plain_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(EXTENSION);
+    plain_path.with_file_name(file_name)
+}
+
+/// Symmetric key the inputs are encrypted with.
+#[derive(Clone)]
+pub struct VaultKey(Key);
+
+impl VaultKey {
+    pub fn generate() -> Self {
+        Self(ChaCha20Poly1305::generate_key(&mut OsRng))
+    }
+
+    /// Reads the key from `AOC_VAULT_KEY` or, if unset, from the keyfile.
+    pub fn load(key_path: &Path) -> anyhow::Result<Self> {
+        if let Ok(hex_key) = std::env::var(KEY_ENV_VAR) {
+            return Self::from_hex(&hex_key)
+                .with_context(|| format!("while reading {KEY_ENV_VAR}"));
+        }
+        let hex_key = std::fs::read_to_string(key_path).with_context(|| {
+            format!(
+                "while reading vault key {} (or set {KEY_ENV_VAR})",
+                key_path.display()
+            )
+        })?;
+        Self::from_hex(&hex_key)
+            .with_context(|| format!("while reading vault key {}", key_path.display()))
+    }
+
+    pub fn from_hex(hex_key: &str) -> anyhow::Result<Self> {
+        let bytes = hex::decode(hex_key.trim()).context("Vault key is not hex encoded.")?;
+        if bytes.len() != 32 {
+            return Err(anyhow::anyhow!(
+                "Vault key has {} bytes instead of 32.",
+                bytes.len()
+            ));
+        }
+        Ok(Self(*Key::from_slice(&bytes)))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Writes the key to the keyfile.
+    pub fn save(&self, key_path: &Path) -> anyhow::Result<()> {
+        std::fs::write(key_path, format!("{}\n", self.to_hex()))
+            .with_context(|| format!("while writing vault key {}", key_path.display()))
+    }
+
+    /// Encrypts the content, bound to the given name so that vaults cannot be swapped.
+    pub fn encrypt(&self, name: &str, plain: &str) -> anyhow::Result<String> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let cipher = ChaCha20Poly1305::new(&self.0)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plain.as_bytes(),
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("Could not encrypt {name}."))?;
+        Ok(format!(
+            "{HEADER}\n{}\n{}\n",
+            hex::encode(nonce),
+            hex::encode(cipher)
+        ))
+    }
+
+    pub fn decrypt(&self, name: &str, vault: &str) -> anyhow::Result<String> {
+        let mut lines = vault.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(anyhow::anyhow!(
+                "Vault of {name} does not start with \"{HEADER}\"."
+            ));
+        }
+        let mut next_hex = |what: &str| {
+            lines
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Vault of {name} has no {what}."))
+                .and_then(|line| {
+                    hex::decode(line).with_context(|| format!("Vault {what} of {name} is no hex."))
+                })
+        };
+        let nonce = next_hex("nonce")?;
+        let cipher = next_hex("ciphertext")?;
+        if nonce.len() != 12 {
+            return Err(anyhow::anyhow!(
+                "Vault nonce of {name} has {} bytes.",
+                nonce.len()
+            ));
+        }
+        let plain = ChaCha20Poly1305::new(&self.0)
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &cipher,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                anyhow::anyhow!("Could not decrypt {name}, the vault key is probably wrong.")
+            })?;
+        String::from_utf8(plain).with_context(|| format!("Decrypted {name} is no UTF-8."))
+    }
+}
+
+/// Reads the plain file if present and decrypts its vault otherwise.
+pub fn read(plain_path: &Path, key_path: &Path) -> anyhow::Result<String> {
+    if plain_path.exists() {
+        return std::fs::read_to_string(plain_path)
+            .with_context(|| format!("while reading {}", plain_path.display()));
+    }
+    let vault_path = vault_path(plain_path);
+    let vault = std::fs::read_to_string(&vault_path)
+        .with_context(|| format!("while reading {}", vault_path.display()))?;
+    VaultKey::load(key_path)?.decrypt(&vault_name(plain_path), &vault)
+}
+
+/// Opens the plain file if present and decrypts its vault into memory otherwise.
+pub fn open(plain_path: &Path, key_path: &Path) -> anyhow::Result<Box<dyn BufRead>> {
+    if plain_path.exists() {
+        let file = File::open(plain_path)
+            .with_context(|| format!("while opening {}", plain_path.display()))?;
+        return Ok(Box::new(BufReader::new(file)));
+    }
+    Ok(Box::new(Cursor::new(read(plain_path, key_path)?)))
+}
+
+/// Encrypts the plain file into its vault and removes the plain file.
+pub fn encrypt_file(key: &VaultKey, plain_path: &Path) -> anyhow::Result<PathBuf> {
+    let plain = std::fs::read_to_string(plain_path)
+        .with_context(|| format!("while reading {}", plain_path.display()))?;
+    let vault_path = vault_path(plain_path);
+    std::fs::write(&vault_path, key.encrypt(&vault_name(plain_path), &plain)?)
+        .with_context(|| format!("while writing {}", vault_path.display()))?;
+    std::fs::remove_file(plain_path)
+        .with_context(|| format!("while removing {}", plain_path.display()))?;
+    Ok(vault_path)
+}
+
+/// Restores the plain file from its vault, keeping the vault.
+pub fn decrypt_file(key: &VaultKey, plain_path: &Path) -> anyhow::Result<()> {
+    let vault_path = vault_path(plain_path);
+    let vault = std::fs::read_to_string(&vault_path)
+        .with_context(|| format!("while reading {}", vault_path.display()))?;
+    let plain = key.decrypt(&vault_name(plain_path), &vault)?;
+    std::fs::write(plain_path, plain)
+        .with_context(|| format!("while writing {}", plain_path.display()))
+}
+
+/// Re-encrypts the vaults of the plain files with another key.
+///
+/// Every vault is decrypted before the first one is rewritten, so a wrong old key changes nothing.
+pub fn rotate_files(
+    old_key: &VaultKey,
+    new_key: &VaultKey,
+    plain_paths: &[PathBuf],
+) -> anyhow::Result<()> {
+    let vaults = plain_paths
+        .iter()
+        .map(|plain_path| {
+            let name = vault_name(plain_path);
+            let vault_path = vault_path(plain_path);
+            let vault = std::fs::read_to_string(&vault_path)
+                .with_context(|| format!("while reading {}", vault_path.display()))?;
+            let plain = old_key.decrypt(&name, &vault)?;
+            Ok((vault_path, new_key.encrypt(&name, &plain)?))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    for (vault_path, vault) in vaults {
+        std::fs::write(&vault_path, vault)
+            .with_context(|| format!("while writing {}", vault_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Replaces the key with a new one and re-encrypts the vaults of the plain files with it.
+///
+/// The old key is backed up to `<key>.old` and the new key is written to `<key>.new` before the
+/// first vault is rewritten, and moved into place last, so a key is always on disk for every vault.
+pub fn rotate(old_key: &VaultKey, key_path: &Path, plain_paths: &[PathBuf]) -> anyhow::Result<()> {
+    let new_key = VaultKey::generate();
+    let backup_path = key_path.with_extension("old");
+    old_key
+        .save(&backup_path)
+        .context("while backing up the old vault key")?;
+    let new_key_path = key_path.with_extension("new");
+    new_key
+        .save(&new_key_path)
+        .context("while storing the new vault key")?;
+    rotate_files(old_key, &new_key, plain_paths).with_context(|| {
+        format!(
+            "while rotating vaults, the old key is in {} and the new one in {}",
+            backup_path.display(),
+            new_key_path.display()
+        )
+    })?;
+    std::fs::rename(&new_key_path, key_path).with_context(|| {
+        format!(
+            "while moving the new vault key {} to {}",
+            new_key_path.display(),
+            key_path.display()
+        )
+    })
+}
+
+/// Name the ciphertext is bound to: the path below `inputs/`, e.g. `2022/alice/day01.input`, so
+/// a vault does not decrypt when it is copied to another year, profile or day.
+///
+/// Paths outside of `inputs/` are bound as a whole.
+fn vault_name(plain_path: &Path) -> String {
+    let components = plain_path.components().collect::<Vec<_>>();
+    let below_inputs = components
+        .iter()
+        .rposition(|component| component.as_os_str() == "inputs")
+        .and_then(|index| components.get(index.checked_add(1)?..))
+        .unwrap_or(&components);
+    below_inputs
+        .iter()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[allow(clippy::panic_in_result_fn)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() -> anyhow::Result<()> {
+        // Arrange
+        let key = VaultKey::generate();
+        let plain = "1000\n2000\n\n3000\n";
+
+        // Act
+        let vault = key.encrypt("inputs/day01.input", plain)?;
+        let decrypted = key.decrypt("inputs/day01.input", &vault)?;
+
+        // Assert
+        assert!(!vault.contains("1000"));
+        assert_eq!(decrypted, plain);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_key_and_name() -> anyhow::Result<()> {
+        // Arrange
+        let key = VaultKey::generate();
+        let vault = key.encrypt("inputs/day01.input", "1000\n")?;
+
+        // Act
+        let with_other_key = VaultKey::generate().decrypt("inputs/day01.input", &vault);
+        let with_other_name = key.decrypt("inputs/day02.input", &vault);
+
+        // Assert
+        assert!(with_other_key.is_err());
+        assert!(with_other_name.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotate_keeps_vaults_readable_if_the_key_cannot_be_written() -> anyhow::Result<()> {
+        // Arrange
+        let directory =
+            std::env::temp_dir().join(format!("aoc-vault-rotate-{}", std::process::id()));
+        std::fs::create_dir_all(&directory)?;
+        let key_path = directory.join(".vault-key");
+        let plain_path = directory.join("day01.input");
+        let key = VaultKey::generate();
+        key.save(&key_path)?;
+        std::fs::write(&plain_path, "1000\n")?;
+        encrypt_file(&key, &plain_path)?;
+        // A directory where the new key should go makes writing it fail.
+        std::fs::create_dir_all(key_path.with_extension("new"))?;
+
+        // Act
+        let rotated = rotate(&key, &key_path, std::slice::from_ref(&plain_path));
+        let vault = std::fs::read_to_string(vault_path(&plain_path))?;
+        let stored_key = VaultKey::from_hex(&std::fs::read_to_string(&key_path)?)?;
+        std::fs::remove_dir_all(&directory)?;
+
+        // Assert
+        assert!(rotated.is_err());
+        assert_eq!(
+            stored_key.decrypt(&vault_name(&plain_path), &vault)?,
+            "1000\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vault_name_binds_year_profile_and_day() {
+        // Act
+        let default_name = vault_name(Path::new("/repo/inputs/2022/day01.input"));
+        let profile_name = vault_name(Path::new("/repo/inputs/2021/alice/day01.input"));
+
+        // Assert
+        assert_eq!(default_name, "2022/day01.input");
+        assert_eq!(profile_name, "2021/alice/day01.input");
+    }
+
+    #[test]
+    fn test_key_hex_round_trip() -> anyhow::Result<()> {
+        // Arrange
+        let key = VaultKey::generate();
+
+        // Act
+        let parsed = VaultKey::from_hex(&key.to_hex())?;
+
+        // Assert
+        assert_eq!(parsed.to_hex(), key.to_hex());
+        assert!(VaultKey::from_hex("abcd").is_err());
+
+        Ok(())
+    }
+}